@@ -0,0 +1,58 @@
+//! The crossovers behind KARATSUBA_THRESHOLD and NEWTON_DIVISION_THRESHOLD
+//! on the BigUint and the Montgomery (p434) prime fields
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use supersingular_elliptic_curve::field::Field;
+use supersingular_elliptic_curve::field::fp::FiniteField;
+use supersingular_elliptic_curve::field::fp_mont::MontgomeryField;
+use supersingular_elliptic_curve::field::params::Fp434;
+use supersingular_elliptic_curve::poly::Polynomial;
+
+const MUL_LENGTHS : [usize; 8] = [4, 8, 12, 16, 20, 24, 32, 64];
+const DIV_LENGTHS : [usize; 7] = [32, 64, 128, 256, 512, 768, 1024];
+
+fn random_poly<F : Field>(field : &F, len : usize) -> Polynomial<F> {
+    Polynomial::new(field, (0..len).map(|_| field.rand(None)).collect())
+}
+
+/// Schoolbook against a single level of Karatsuba on top of it :
+/// with the threshold n, both halves of a product of length n are done by the schoolbook multiplication
+fn bench_mul<F : Field>(c : &mut Criterion, name : &str, field : &F) {
+    let mut group = c.benchmark_group(format!("mul/{}", name));
+    for n in MUL_LENGTHS {
+        let (a, b) = (random_poly(field, n), random_poly(field, n));
+        group.bench_with_input(BenchmarkId::new("schoolbook", n), &n, |bench, _| bench.iter(|| a.mul_with_threshold(&b, usize::MAX)));
+        group.bench_with_input(BenchmarkId::new("karatsuba", n), &n, |bench, &n| bench.iter(|| a.mul_with_threshold(&b, n)));
+    }
+    group.finish();
+}
+
+/// Balanced divisions, the quotient and the divisor have the same length n
+fn bench_div_rem<F : Field>(c : &mut Criterion, name : &str, field : &F) {
+    let mut group = c.benchmark_group(format!("div_rem/{}", name));
+    for n in DIV_LENGTHS {
+        let (a, b) = (random_poly(field, 2 * n - 1), random_poly(field, n));
+        group.bench_with_input(BenchmarkId::new("schoolbook", n), &n, |bench, _| bench.iter(|| a.div_rem_schoolbook(&b)));
+        group.bench_with_input(BenchmarkId::new("newton", n), &n, |bench, _| bench.iter(|| a.div_rem_newton(&b)));
+    }
+    group.finish();
+}
+
+fn thresholds(c : &mut Criterion) {
+    let prime = Fp434::new().prime();
+    let fp = FiniteField::new(&prime);
+    let fp_mont = MontgomeryField::<7>::new(&prime);
+
+    bench_mul(c, "fp434", &fp);
+    bench_mul(c, "fp434_mont", &fp_mont);
+    bench_div_rem(c, "fp434", &fp);
+    bench_div_rem(c, "fp434_mont", &fp_mont);
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(20).warm_up_time(Duration::from_millis(500)).measurement_time(Duration::from_secs(1));
+    targets = thresholds
+);
+criterion_main!(benches);
@@ -4,45 +4,83 @@ use num_rational::BigRational;
 use std::ops::{Add, Mul};
 use num_prime::buffer::{NaiveBuffer, PrimeBufferExt};
 use impl_ops::impl_bin_ops;
+use std::sync::Arc;
+use std::fmt;
+use std::str::FromStr;
+use crate::sage::{self, ParseError};
 
 /// B_p,\infty
 #[derive(Clone, Debug)]
 pub struct QuaternionAlgebra {
-    prime : BigUint
+    prime : Arc<BigUint>
 }
 
-impl<'a> QuaternionAlgebra {
+impl QuaternionAlgebra {
     pub fn new(prime : &BigUint) -> Self {
         let pb = NaiveBuffer::new();
         assert!(pb.is_prime(prime, None).probably(), "The quaternion base number is not prime!");
         
-        QuaternionAlgebra { prime: prime.clone() }
+        QuaternionAlgebra { prime: Arc::new(prime.clone()) }
     }
 
-    pub fn zero(&'a self) -> QuaternionAlgebraElement<'a> {
-        QuaternionAlgebraElement { algebra: self, coefficient: [BigRational::default(), BigRational::default(), BigRational::default(), BigRational::default()] }
+    pub fn zero(&self) -> QuaternionAlgebraElement {
+        QuaternionAlgebraElement { algebra: self.clone(), coefficient: [BigRational::default(), BigRational::default(), BigRational::default(), BigRational::default()] }
     }
 
-    pub fn gen(&'a self, coefficient : [BigRational;4]) -> QuaternionAlgebraElement<'a> {
+    pub fn gen(&self, coefficient : [BigRational;4]) -> QuaternionAlgebraElement {
         QuaternionAlgebraElement {
-            algebra : self,
+            algebra : self.clone(),
             coefficient
         }
     }
+
+    /// Parse an element a + b*i + c*j + d*k with rational coefficients
+    pub fn element_from_str(&self, s : &str) -> Result<QuaternionAlgebraElement, ParseError> {
+        let mut result = self.zero();
+        for term in sage::parse_sum(s, &["i", "j", "k"])? {
+            let index = match (term.variable, term.exponent) {
+                (None, _) => 0,
+                (Some(variable), 1) => variable + 1,
+                _ => return Err(ParseError::InvalidSyntax),
+            };
+            let c = BigRational::from_str(term.coefficient).map_err(|_| ParseError::InvalidNumber)?;
+            match term.negative {
+                true => result.coefficient[index] -= c,
+                false => result.coefficient[index] += c,
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl fmt::Display for QuaternionAlgebra {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Quaternion Algebra (-1, -{}) with base ring Rational Field", self.prime)
+    }
 }
 
 /// A quaternion element consists of four rational coefficients
 #[derive(Clone, Debug)]
-pub struct QuaternionAlgebraElement<'a> {
-    algebra : &'a QuaternionAlgebra,
+pub struct QuaternionAlgebraElement {
+    algebra : QuaternionAlgebra,
     coefficient : [BigRational;4],
 }
 
+/// Sage syntax a + b*i + c*j + d*k
+impl fmt::Display for QuaternionAlgebraElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms : Vec<(String, String)> = self.coefficient.iter().zip(["", "i", "j", "k"])
+            .map(|(c, monomial)| (c.to_string(), monomial.to_string()))
+            .collect();
+        sage::write_sum(f, &terms)
+    }
+}
+
 #[impl_bin_ops]
-impl<'a> Add for QuaternionAlgebraElement<'a> {
-    fn add(self, rhs: QuaternionAlgebraElement<'a>) -> QuaternionAlgebraElement<'a> {
+impl Add for QuaternionAlgebraElement {
+    fn add(self, rhs: QuaternionAlgebraElement) -> QuaternionAlgebraElement {
         QuaternionAlgebraElement {
-            algebra : self.algebra,
+            algebra : self.algebra.clone(),
             coefficient : 
                 [&self.coefficient[0] + &rhs.coefficient[0],
                 &self.coefficient[1] + &rhs.coefficient[1],
@@ -53,8 +91,8 @@ impl<'a> Add for QuaternionAlgebraElement<'a> {
 }
 
 #[impl_bin_ops]
-impl<'a> Mul for QuaternionAlgebraElement<'a> {
-    fn mul(self, rhs: QuaternionAlgebraElement<'a>) -> QuaternionAlgebraElement<'a> {
+impl Mul for QuaternionAlgebraElement {
+    fn mul(self, rhs: QuaternionAlgebraElement) -> QuaternionAlgebraElement {
         let mut result = self.algebra.zero();
         result.coefficient[0] = &self.coefficient[0] * &rhs.coefficient[0] -
                                 &self.coefficient[1] * &rhs.coefficient[1] -
@@ -83,6 +121,7 @@ mod tests {
     use num_rational::BigRational;
 
     use super::QuaternionAlgebra;
+    use crate::sage::ParseError;
     #[test]
     fn quaternion_test() {
         let quaternion_alg = QuaternionAlgebra::new(&BigUint::from(13 as u32));
@@ -90,4 +129,23 @@ mod tests {
         let b = &a * &a;
         println!("{:?}", &b + &b);
     }
+
+    #[test]
+    fn quaternion_display_test() {
+        let quaternion_alg = QuaternionAlgebra::new(&BigUint::from(13_u32));
+        let q = |n : [i32; 4], d : i32| quaternion_alg.gen(n.map(|n| BigRational::new(BigInt::from(n), BigInt::from(d))));
+        assert_eq!(quaternion_alg.to_string(), "Quaternion Algebra (-1, -13) with base ring Rational Field");
+
+        let cases = [
+            (q([1, -3, 0, 4], 2), "1/2 - 3/2*i + 2*k"),
+            (q([0, 1, -1, 0], 1), "i - j"),
+            (q([-3, 0, 0, 0], 4), "-3/4"),
+            (q([0, 0, 0, 0], 1), "0"),
+        ];
+        for (element, expected) in cases {
+            assert_eq!(element.to_string(), expected);
+            assert_eq!(quaternion_alg.element_from_str(expected).map(|x| x.coefficient), Ok(element.coefficient));
+        }
+        assert_eq!(quaternion_alg.element_from_str("1 + i*j").map(|x| x.coefficient), Err(ParseError::InvalidNumber));
+    }
 }
\ No newline at end of file
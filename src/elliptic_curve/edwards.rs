@@ -0,0 +1,493 @@
+//! The twisted Edwards curves a * x^2 + y^2 = 1 + d * x^2 * y^2
+//!
+//! The characteristic must not be 2. The points have the completed coordinates ((X : Z), (Y : T)) in P^1 x P^1
+//! with x = X / Z and y = Y / T, following Bernstein and Lange, "A complete set of addition laws for incomplete Edwards curves".
+//! They include the points at infinity, which exist when d or a / d is a square, so the group law is defined everywhere.
+//! The curve is isomorphic to the montgomery curve B * y^2 = x^3 + A * x^2 + x with A = 2(a + d) / (a - d) and B = 4 / (a - d).
+use crate::field::{Field, FieldElement};
+use crate::group::{Group, GroupElement};
+use crate::sage;
+use super::mont_curve::{MontgomeryCurve, MontgomeryCurvePoint};
+use super::PointError;
+use std::ops::{Neg, Mul};
+use std::fmt;
+use std::sync::Arc;
+use num::BigInt;
+use impl_ops::impl_bin_ops;
+use rand::RngCore;
+
+/// The structure of a twisted Edwards curve
+/// It's a cheap handle to the shared coefficients like [MontgomeryCurve]
+#[derive(Clone, Debug)]
+pub struct TwistedEdwardsCurve<F> where F: Field {
+    field : F,
+    coefficients : Arc<Coefficients<F::Element>>,
+}
+
+/// The coefficients a and d of a twisted Edwards curve
+#[derive(Debug)]
+struct Coefficients<T> {
+    a : T,
+    d : T,
+}
+
+/// The structure of a point ((x : z), (y : t)) of a twisted Edwards curve, the neutral element is ((0 : 1), (1 : 1))
+/// The curve is shared by all the points derived from it like [MontgomeryCurvePoint]
+#[derive(Clone, Debug)]
+pub struct TwistedEdwardsCurvePoint<F> where F: Field {
+    curve : TwistedEdwardsCurve<F>,
+    x : F::Element,
+    z : F::Element,
+    y : F::Element,
+    t : F::Element,
+}
+
+/// The completed coordinates ((x, z), (y, t)) of a point
+pub type CompletedCoordinates<'a, T> = ((&'a T, &'a T), (&'a T, &'a T));
+
+/// The isomorphism (u, v) -> (u / v, (u - 1) / (u + 1)) from a montgomery curve to a twisted Edwards curve
+/// with a = (A + 2) / B and d = (A - 2) / B
+#[derive(Clone, Debug)]
+pub struct MontgomeryEdwardsMap<F> where F: Field {
+    montgomery : MontgomeryCurve<F>,
+    edwards : TwistedEdwardsCurve<F>,
+}
+
+impl<F> TwistedEdwardsCurve<F> where F: Field {
+    /// The curve must be nonsingular, i.e. a, d and a - d are nonzero
+    pub fn new(field : &F, a : F::Element, d : F::Element) -> Self {
+        assert!(!a.is_zero() && !d.is_zero() && a != d, "The curve is singular!");
+        TwistedEdwardsCurve {
+            field : field.clone(),
+            coefficients : Arc::new(Coefficients { a, d }),
+        }
+    }
+
+    /// The field of definition
+    pub fn field(&self) -> &F {
+        &self.field
+    }
+
+    /// The coefficients (a, d)
+    pub fn coefficients(&self) -> (&F::Element, &F::Element) {
+        (&self.coefficients.a, &self.coefficients.d)
+    }
+
+    /// The point ((x : z), (y : t)) without any check, see [point](Self::point) for the validated one
+    pub fn gen(&self, ((x, z), (y, t)) : CompletedCoordinates<'_, F::Element>) -> TwistedEdwardsCurvePoint<F> {
+        TwistedEdwardsCurvePoint {
+            curve : self.clone(),
+            x : x.clone(), z : z.clone(),
+            y : y.clone(), t : t.clone(),
+        }
+    }
+
+    /// The point ((x : z), (y : t)) if it's a point of the completed curve
+    pub fn point(&self, coordinates : CompletedCoordinates<'_, F::Element>) -> Result<TwistedEdwardsCurvePoint<F>, PointError> {
+        let point = self.gen(coordinates);
+        if (point.x.is_zero() && point.z.is_zero()) || (point.y.is_zero() && point.t.is_zero()) {
+            return Err(PointError::InvalidCoordinates);
+        }
+        match point.is_on_curve() {
+            true => Ok(point),
+            false => Err(PointError::NotOnCurve),
+        }
+    }
+
+    /// 16(a^2 + 14ad + d^2)^3 / (ad(a - d)^4)
+    pub fn j_invariant(&self) -> F::Element {
+        let (a, d) = (&self.coefficients.a, &self.coefficients.d);
+        let t = a.clone() * a.clone() + a.clone() * d.clone() * BigInt::from(14) + d.clone() * d.clone();
+        let a_minus_d_sqr = (a.clone() - d.clone()) * (a.clone() - d.clone());
+        t.clone() * t.clone() * t * BigInt::from(16) * (a.clone() * d.clone() * a_minus_d_sqr.clone() * a_minus_d_sqr).inv()
+    }
+
+    /// Generate a random affine point using the thread-local random number generator
+    pub fn rand(&self) -> TwistedEdwardsCurvePoint<F> {
+        self.rand_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Generate a random affine point using the given random number generator
+    pub fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> TwistedEdwardsCurvePoint<F> {
+        let one = self.field.one();
+        loop {
+            // x^2 = (1 - y^2) / (a - d * y^2)
+            let y = self.field.rand_with_rng(rng, None);
+            let y_sqr = y.clone() * y.clone();
+            let denominator = self.coefficients.a.clone() - self.coefficients.d.clone() * y_sqr.clone();
+            if denominator.is_zero() {
+                continue;
+            }
+            if let Some(x) = ((one.clone() - y_sqr) * denominator.inv()).sqrt_with_rng(rng) {
+                return self.gen(((&x, &one), (&y, &one)));
+            }
+        }
+    }
+
+    /// The isomorphism from the montgomery curve with A = 2(a + d) / (a - d) and B = 4 / (a - d)
+    pub fn montgomery_map(&self) -> MontgomeryEdwardsMap<F> {
+        let a_minus_d_inv = (self.coefficients.a.clone() - self.coefficients.d.clone()).inv();
+        let coefficient = (self.coefficients.a.clone() + self.coefficients.d.clone()) * BigInt::from(2) * a_minus_d_inv.clone();
+        MontgomeryEdwardsMap {
+            montgomery : MontgomeryCurve::new_with_b(&self.field, coefficient, a_minus_d_inv * BigInt::from(4)),
+            edwards : self.clone(),
+        }
+    }
+}
+
+impl<F> MontgomeryCurve<F> where F: Field {
+    /// The isomorphism to the twisted Edwards curve with a = (A + 2) / B and d = (A - 2) / B
+    pub fn edwards_map(&self) -> MontgomeryEdwardsMap<F> {
+        let field = self.field();
+        let two = field.one() * BigInt::from(2);
+        let (coefficient, b) = self.coefficients();
+        let b_inv = b.inv();
+        MontgomeryEdwardsMap {
+            montgomery : self.clone(),
+            edwards : TwistedEdwardsCurve::new(field, (coefficient.clone() + two.clone()) * b_inv.clone(), (coefficient.clone() - two) * b_inv),
+        }
+    }
+}
+
+impl<F> MontgomeryEdwardsMap<F> where F: Field {
+    pub fn montgomery_curve(&self) -> &MontgomeryCurve<F> {
+        &self.montgomery
+    }
+
+    pub fn edwards_curve(&self) -> &TwistedEdwardsCurve<F> {
+        &self.edwards
+    }
+
+    /// (u : v : w) -> ((u : v), (u - w : u + w)), the point at infinity and (0, 0) go to ((0 : 1), (1 : 1)) and ((0 : 1), (-1 : 1))
+    /// The other points of order 2 and the points with u = -w go to the points at infinity of the Edwards curve
+    pub fn to_edwards(&self, point : &MontgomeryCurvePoint<F>) -> TwistedEdwardsCurvePoint<F> {
+        let field = &self.edwards.field;
+        let (u, v, w) = point.coordinates();
+        if point.is_zero() {
+            return self.edwards.zero();
+        }
+        if u.is_zero() {
+            return self.edwards.gen(((&field.zero(), &field.one()), (&-field.one(), &field.one())));
+        }
+        self.edwards.gen(((u, v), (&(u.clone() - w.clone()), &(u.clone() + w.clone()))))
+    }
+
+    /// ((x : z), (y : t)) -> ((t + y)x : (t + y)z : (t - y)x), ((0 : 1), (1 : 1)) and ((0 : 1), (-1 : 1)) go to the point at infinity and (0, 0)
+    pub fn to_montgomery(&self, point : &TwistedEdwardsCurvePoint<F>) -> MontgomeryCurvePoint<F> {
+        let field = &self.edwards.field;
+        if point.x.is_zero() {
+            return match point.is_zero() {
+                true => self.montgomery.zero(),
+                false => self.montgomery.gen((&field.zero(), &field.zero(), &field.one())),
+            };
+        }
+        let t_plus_y = point.t.clone() + point.y.clone();
+        let u = t_plus_y.clone() * point.x.clone();
+        let w = (point.t.clone() - point.y.clone()) * point.x.clone();
+        self.montgomery.gen((&u, &(t_plus_y * point.z.clone()), &w))
+    }
+}
+
+/// e.g. `Twisted Edwards Curve defined by x^2 + y^2 = 1 + 2*x^2*y^2 over Finite Field of size 13`
+impl<F> fmt::Display for TwistedEdwardsCurve<F> where F: Field + fmt::Display, F::Element: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let one = self.field.one().to_string();
+        write!(f, "Twisted Edwards Curve defined by ")?;
+        sage::write_sum(f, &[(self.coefficients.a.to_string(), "x^2".to_string()), (one.clone(), "y^2".to_string())])?;
+        write!(f, " = ")?;
+        sage::write_sum(f, &[(one, String::new()), (self.coefficients.d.to_string(), "x^2*y^2".to_string())])?;
+        write!(f, " over {}", self.field)
+    }
+}
+
+impl<F> Group for TwistedEdwardsCurve<F> where F: Field {
+    type Element = TwistedEdwardsCurvePoint<F>;
+    fn zero(&self) -> Self::Element {
+        let (zero, one) = (self.field.zero(), self.field.one());
+        self.gen(((&zero, &one), (&one, &one)))
+    }
+}
+
+impl<F> TwistedEdwardsCurvePoint<F> where F: Field {
+    /// The point ((x : z), (y : t)) on the same curve sharing its handle
+    fn with_coordinates(&self, ((x, z), (y, t)) : CompletedCoordinates<'_, F::Element>) -> Self {
+        TwistedEdwardsCurvePoint {
+            curve : self.curve.clone(),
+            x : x.clone(), z : z.clone(),
+            y : y.clone(), t : t.clone(),
+        }
+    }
+
+    /// The neutral element of the same curve
+    fn identity(&self) -> Self {
+        let (zero, one) = (self.curve.field.zero(), self.curve.field.one());
+        self.with_coordinates(((&zero, &one), (&one, &one)))
+    }
+
+    /// The completed coordinates ((x, z), (y, t))
+    pub fn coordinates(&self) -> CompletedCoordinates<'_, F::Element> {
+        ((&self.x, &self.z), (&self.y, &self.t))
+    }
+
+    /// Whether it's one of the points at infinity z = 0 or t = 0
+    pub fn is_infinite(&self) -> bool {
+        self.z.is_zero() || self.t.is_zero()
+    }
+
+    /// Whether it satisfies a x^2 t^2 + y^2 z^2 = z^2 t^2 + d x^2 y^2
+    pub fn is_on_curve(&self) -> bool {
+        let (x_sqr, z_sqr) = (self.x.clone() * self.x.clone(), self.z.clone() * self.z.clone());
+        let (y_sqr, t_sqr) = (self.y.clone() * self.y.clone(), self.t.clone() * self.t.clone());
+        let lhs = self.curve.coefficients.a.clone() * x_sqr.clone() * t_sqr.clone() + y_sqr.clone() * z_sqr.clone();
+        lhs == z_sqr * t_sqr + self.curve.coefficients.d.clone() * x_sqr * y_sqr
+    }
+}
+
+/// The reduced coordinates ((x : 1), (y : 1)), with (1 : 0) at infinity
+impl<F> fmt::Display for TwistedEdwardsCurvePoint<F> where F: Field, F::Element: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reduce = |numerator : &F::Element, denominator : &F::Element| match denominator.is_zero() {
+            true => "(1 : 0)".to_string(),
+            false => format!("({} : 1)", numerator.clone() * denominator.inv()),
+        };
+        write!(f, "({}, {})", reduce(&self.x, &self.z), reduce(&self.y, &self.t))
+    }
+}
+
+impl<F> GroupElement for TwistedEdwardsCurvePoint<F> where F: Field {
+    fn is_zero(&self) -> bool {
+        self.x.is_zero() && self.y == self.t
+    }
+}
+
+impl<F> PartialEq for TwistedEdwardsCurvePoint<F> where F: Field {
+    fn eq(&self, rhs : &Self) -> bool {
+        super::projective_eq(&[&self.x, &self.z], &[&rhs.x, &rhs.z]) && super::projective_eq(&[&self.y, &self.t], &[&rhs.y, &rhs.t])
+    }
+}
+
+/// ((-x : z), (y : t))
+impl<F> Neg for TwistedEdwardsCurvePoint<F> where F: Field {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.with_coordinates(((&-self.x.clone(), &self.z), (&self.y, &self.t)))
+    }
+}
+
+/// The two addition laws of Bernstein and Lange on the completed curve a x^2 + y^2 = 1 + d x^2 y^2, both without inversion
+/// For all the pairs of points, each of (x : z) and (y : t) is (0 : 0) under at most one of the laws
+/// and the correct value otherwise, so the sum is defined everywhere including the points at infinity
+pub(crate) fn add_completed<T : FieldElement>(a : &T, d : &T, ((x1, z1), (y1, t1)) : CompletedCoordinates<'_, T>, ((x2, z2), (y2, t2)) : CompletedCoordinates<'_, T>) -> ((T, T), (T, T)) {
+    // the first law from x3 = (x1 y2 + x2 y1) / (1 + d x1 x2 y1 y2) and y3 = (y1 y2 - a x1 x2) / (1 - d x1 x2 y1 y2)
+    let (x1_x2, y1_y2) = (x1.clone() * x2.clone(), y1.clone() * y2.clone());
+    let (z1_z2, t1_t2) = (z1.clone() * z2.clone(), t1.clone() * t2.clone());
+    let (z_t, e) = (z1_z2.clone() * t1_t2.clone(), d.clone() * x1_x2.clone() * y1_y2.clone());
+    let x_y2 = x1.clone() * y2.clone() * z2.clone() * t1.clone();
+    let y_x2 = x2.clone() * y1.clone() * z1.clone() * t2.clone();
+    let (x, z) = (x_y2.clone() + y_x2.clone(), z_t.clone() + e.clone());
+    let (y, t) = (y1_y2.clone() * z1_z2.clone() - a.clone() * x1_x2.clone() * t1_t2.clone(), z_t - e);
+
+    // the second law from x3 = (x1 y1 + x2 y2) / (y1 y2 + a x1 x2) and y3 = (x1 y1 - x2 y2) / (x1 y2 - y1 x2)
+    let (x, z) = match x.is_zero() && z.is_zero() {
+        true => {
+            let xy1 = x1.clone() * y1.clone() * z2.clone() * t2.clone();
+            let xy2 = x2.clone() * y2.clone() * z1.clone() * t1.clone();
+            (xy1 + xy2, a.clone() * x1_x2.clone() * t1_t2.clone() + y1_y2.clone() * z1_z2.clone())
+        },
+        false => (x, z),
+    };
+    let (y, t) = match y.is_zero() && t.is_zero() {
+        true => {
+            let xy1 = x1.clone() * y1.clone() * z2.clone() * t2.clone();
+            let xy2 = x2.clone() * y2.clone() * z1.clone() * t1.clone();
+            (xy1 - xy2, x_y2 - y_x2)
+        },
+        false => (y, t),
+    };
+    ((x, z), (y, t))
+}
+
+/// The addition laws of [add_completed], defined for all the pairs of points
+#[impl_bin_ops]
+impl<F> Add for TwistedEdwardsCurvePoint<F> where F: Field {
+    fn add(self, rhs : TwistedEdwardsCurvePoint<F>) -> TwistedEdwardsCurvePoint<F> {
+        let ((x, z), (y, t)) = add_completed(&self.curve.coefficients.a, &self.curve.coefficients.d, self.coordinates(), rhs.coordinates());
+        self.with_coordinates(((&x, &z), (&y, &t)))
+    }
+}
+
+#[impl_bin_ops]
+impl<F> Sub for TwistedEdwardsCurvePoint<F> where F: Field {
+    fn sub(self, rhs : TwistedEdwardsCurvePoint<F>) -> TwistedEdwardsCurvePoint<F> {
+        self + (-rhs.clone())
+    }
+}
+
+/// Scalar multiplication
+impl<F> Mul<BigInt> for TwistedEdwardsCurvePoint<F> where F: Field {
+    type Output = Self;
+    fn mul(self, rhs : BigInt) -> TwistedEdwardsCurvePoint<F> {
+        let identity = self.identity();
+        super::scalar_mul(self, identity, &rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_curve::affine_points;
+    use crate::field::fp::FiniteField;
+    use crate::field::fp2::FiniteField2;
+    use num::BigUint;
+
+    /// All the points of the completed curve over a small prime field, including the points at infinity
+    fn all_points(curve : &TwistedEdwardsCurve<FiniteField>) -> Vec<TwistedEdwardsCurvePoint<FiniteField>> {
+        let fp = curve.field();
+        let (zero, one) = (fp.zero(), fp.one());
+        let mut points = affine_points(fp, |x, y| curve.point(((x, &one), (y, &one))).ok());
+        for c in num::range(BigInt::from(0), fp.characteristic()).map(|c| fp.gen(&c)) {
+            points.extend(curve.point(((&one, &zero), (&c, &one))));
+            points.extend(curve.point(((&c, &one), (&one, &zero))));
+        }
+        points
+    }
+
+    /// The sum is a valid point, the coordinates (0 : 0) would compare equal to every point
+    fn checked_sum(p : &TwistedEdwardsCurvePoint<FiniteField>, q : &TwistedEdwardsCurvePoint<FiniteField>) -> TwistedEdwardsCurvePoint<FiniteField> {
+        let sum = p + q;
+        assert_eq!(p.curve.point(sum.coordinates()).as_ref(), Ok(&sum));
+        sum
+    }
+
+    #[test]
+    fn edwards_group_law_test() {
+        // complete since 1 is a square and 2 is not modulo 13
+        let fp = FiniteField::new(&BigUint::from(13_u32));
+        let c = |n : i64| fp.gen(&BigInt::from(n));
+        let curve = TwistedEdwardsCurve::new(&fp, c(1), c(2));
+        let points = all_points(&curve);
+        assert!(points.iter().all(|point| !point.is_infinite()));
+        assert!(points.iter().all(|point| Arc::ptr_eq(&point.curve.coefficients, &curve.coefficients)));
+        let order = BigInt::from(points.len());
+        for p in &points {
+            assert!((p + (-p.clone())).is_zero());
+            assert!((p.clone() * order.clone()).is_zero());
+            for q in &points {
+                assert!(points.contains(&checked_sum(p, q)));
+                assert_eq!(p + q, q + p);
+                for r in &points {
+                    assert_eq!((p + q) + r, p + (q + r));
+                }
+            }
+        }
+
+        // every curve over small primes, with the points at infinity when d or a / d is a square
+        // the maps are isomorphisms onto the montgomery curves, so the sums are the montgomery ones
+        for prime in [5_u32, 7, 11, 13] {
+            let fp = FiniteField::new(&BigUint::from(prime));
+            for (a, d) in (1..prime).flat_map(|a| (1..prime).filter(move |d| *d != a).map(move |d| (a, d))) {
+                let curve = TwistedEdwardsCurve::new(&fp, fp.gen(&BigInt::from(a)), fp.gen(&BigInt::from(d)));
+                let map = curve.montgomery_map();
+                let montgomery = map.montgomery_curve();
+                let points = all_points(&curve);
+                let one = fp.one();
+                let montgomery_order = 1 + affine_points(&fp, |x, y| montgomery.point((x, y, &one)).ok()).len();
+                assert_eq!(points.len(), montgomery_order);
+                let order = BigInt::from(points.len());
+                for p in &points {
+                    let p_m = map.to_montgomery(p);
+                    assert!(p_m.is_on_curve());
+                    assert_eq!(map.to_edwards(&p_m), *p);
+                    assert!((p.clone() * order.clone()).is_zero());
+                    for q in &points {
+                        let sum = checked_sum(p, q);
+                        assert_eq!(map.to_montgomery(&sum), p_m.clone() + map.to_montgomery(q));
+                    }
+                }
+            }
+        }
+
+        assert_eq!(curve.to_string(), "Twisted Edwards Curve defined by x^2 + y^2 = 1 + 2*x^2*y^2 over Finite Field of size 13");
+        assert_eq!(curve.zero().to_string(), "((0 : 1), (1 : 1))");
+        assert_eq!(curve.point(((&c(0), &c(0)), (&c(2), &c(1)))), Err(PointError::InvalidCoordinates));
+        assert_eq!(curve.point(((&c(1), &c(1)), (&c(1), &c(1)))), Err(PointError::NotOnCurve));
+        assert_eq!(curve.point(((&c(2), &c(2)), (&c(0), &c(5)))).unwrap().to_string(), "((1 : 1), (0 : 1))");
+
+        // 4 is a square modulo 13, so x^2 + y^2 = 1 + 4x^2y^2 has the points ((1 : ±2), (1 : 0)) of order 4
+        // over (0, -1) and the points ((1 : 0), (±1 : 2)) of order 2
+        let curve = TwistedEdwardsCurve::new(&fp, c(1), c(4));
+        let infinite = curve.point(((&c(1), &c(2)), (&c(1), &c(0)))).unwrap();
+        assert_eq!(infinite.to_string(), "((7 : 1), (1 : 0))");
+        assert!(infinite.is_infinite() && !(infinite.clone() * BigInt::from(2)).is_zero());
+        assert_eq!(infinite.clone() + infinite, curve.point(((&c(0), &c(1)), (&c(-1), &c(1)))).unwrap());
+        let infinite = curve.point(((&c(1), &c(0)), (&c(1), &c(2)))).unwrap();
+        assert!(infinite.is_infinite() && (infinite.clone() + infinite).is_zero());
+    }
+
+    #[test]
+    fn montgomery_edwards_test() {
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(430_u32));
+        let c = |a : i64, b : i64| fp2.gen((&BigInt::from(a), &BigInt::from(b)));
+
+        let montgomery = MontgomeryCurve::new(&fp2, c(3, 6));
+        let map = montgomery.edwards_map();
+        let edwards = map.edwards_curve();
+        assert_eq!(edwards.j_invariant(), montgomery.j_invariant());
+        let back = edwards.montgomery_map();
+        assert_eq!(back.montgomery_curve().coefficients(), montgomery.coefficients());
+        for _ in 0..10 {
+            let (p, q) = (edwards.rand(), edwards.rand());
+            assert!(p.is_on_curve());
+            let (p_m, q_m) = (map.to_montgomery(&p), map.to_montgomery(&q));
+            assert!(p_m.is_on_curve());
+            assert_eq!(map.to_montgomery(&(p.clone() + q.clone())), p_m.clone() + q_m);
+            assert_eq!(map.to_edwards(&p_m), p.clone());
+            assert_eq!(back.to_edwards(&back.to_montgomery(&p)), p.clone());
+            assert!((p.clone() - p.clone()).is_zero());
+        }
+
+        // (0, 0) and the other points of order 2, which go to infinity
+        let t = montgomery.gen((&c(0, 0), &c(0, 0), &c(1, 0)));
+        let t_e = map.to_edwards(&t);
+        assert_eq!(t_e, edwards.gen(((&c(0, 0), &c(1, 0)), (&c(-1, 0), &c(1, 0)))));
+        assert!((t_e.clone() + t_e.clone()).is_zero());
+        assert_eq!(map.to_montgomery(&t_e), t);
+        for point in montgomery.torsion_points(2).iter().filter(|&point| !point.coordinates().0.is_zero()) {
+            let point_e = map.to_edwards(point);
+            assert!(point_e.is_on_curve() && point_e.is_infinite());
+            assert!((point_e.clone() + point_e.clone()).is_zero());
+            assert_eq!(map.to_montgomery(&point_e), *point);
+        }
+
+        // A = 6 gives a = 8 and d = 4, a square : the points of order 4 over the other points of order 2 add up to infinity
+        let montgomery = MontgomeryCurve::new(&fp2, c(6, 0));
+        let map = montgomery.edwards_map();
+        let two = BigInt::from(2);
+        let q = montgomery.torsion_points(4).into_iter()
+            .find(|q| !(q.clone() * two.clone()).is_zero() && !(q.clone() * two.clone()).coordinates().0.is_zero()).unwrap();
+        let q_e = map.to_edwards(&q);
+        assert!(!q_e.is_infinite());
+        assert_eq!(q_e.clone() * BigInt::from(1), q_e);
+        let double = q_e.clone() + q_e.clone();
+        assert!(double.is_on_curve() && double.is_infinite());
+        assert_eq!(q_e.clone() * two.clone(), double);
+        assert_eq!(map.to_montgomery(&double), q.clone() * two);
+        assert_eq!(q_e.clone() * BigInt::from(3), -q_e.clone());
+        assert!((double.clone() + double).is_zero());
+        for _ in 0..10 {
+            let p = montgomery.rand();
+            assert_eq!(map.to_edwards(&p) + q_e.clone(), map.to_edwards(&(p + q.clone())));
+        }
+
+        // the twist with B = 2 + i
+        let twist = MontgomeryCurve::new_with_b(&fp2, c(3, 6), c(2, 1));
+        let map = twist.edwards_map();
+        for _ in 0..10 {
+            let (p, q) = (twist.rand(), twist.rand());
+            assert!(map.to_edwards(&p).is_on_curve());
+            assert_eq!(map.to_edwards(&p) + map.to_edwards(&q), map.to_edwards(&(p.clone() + q)));
+            assert_eq!(map.to_montgomery(&map.to_edwards(&p)), p);
+        }
+    }
+}
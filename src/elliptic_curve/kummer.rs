@@ -0,0 +1,235 @@
+//! x-only arithmetic on the Kummer line of a montgomery curve
+//!
+//! A point is represented by (X : Z) with x = X / Z, so P and -P share a representation.
+//! The curve constants are kept projective as (A + 2C : 4C) for the curve By^2 = x^3 + (A / C) x^2 + x,
+//! which makes every formula free of inversions.
+use crate::field::{Field, FieldElement};
+use super::projective::ProjectiveMontgomeryCurve;
+use std::fmt;
+use num::BigInt;
+use num::bigint::Sign;
+
+/// The Kummer line of a montgomery curve with the constants (A + 2C : 4C)
+#[derive(Clone, Debug)]
+pub struct KummerLine<F> where F: Field {
+    field : F,
+    a24_plus : F::Element,
+    c24 : F::Element,
+}
+
+/// The x-only point (X : Z), the point at infinity is (1 : 0)
+#[derive(Clone, Debug)]
+pub struct KummerPoint<F> where F: Field {
+    x : F::Element,
+    z : F::Element,
+}
+
+impl<F> KummerLine<F> where F: Field {
+    /// The constants (A + 2C : 4C), c24 must be nonzero
+    pub fn new(field : &F, a24_plus : F::Element, c24 : F::Element) -> Self {
+        assert!(!c24.is_zero(), "The constant 4C must be nonzero");
+        KummerLine {
+            field : field.clone(),
+            a24_plus,
+            c24,
+        }
+    }
+
+    /// The constants (A + 2C, 4C)
+    pub fn constants(&self) -> (&F::Element, &F::Element) {
+        (&self.a24_plus, &self.c24)
+    }
+
+    /// The curve of the constants without inversion
+    pub fn curve(&self) -> ProjectiveMontgomeryCurve<F> {
+        ProjectiveMontgomeryCurve::from_a24_plus(&self.field, &self.a24_plus, &self.c24)
+    }
+
+    /// The point at infinity (1 : 0)
+    pub fn zero(&self) -> KummerPoint<F> {
+        KummerPoint::new(self.field.one(), self.field.zero())
+    }
+
+    /// The point (x : 1)
+    pub fn point(&self, x : &F::Element) -> KummerPoint<F> {
+        KummerPoint::new(x.clone(), self.field.one())
+    }
+
+    /// x([2]P)
+    /// X = 4C (X - Z)^2 (X + Z)^2 and Z = 4XZ (4C (X - Z)^2 + (A + 2C) 4XZ)
+    pub fn xdbl(&self, p : &KummerPoint<F>) -> KummerPoint<F> {
+        let t0 = (p.x.clone() - p.z.clone()) * (p.x.clone() - p.z.clone());
+        let t1 = (p.x.clone() + p.z.clone()) * (p.x.clone() + p.z.clone());
+        let e = t1.clone() - t0.clone();
+        let z = self.c24.clone() * t0;
+        KummerPoint::new(z.clone() * t1, (z + self.a24_plus.clone() * e.clone()) * e)
+    }
+
+    /// The differential addition x(P + Q) from x(P), x(Q) and x(P - Q)
+    /// x(P - Q) must not be 0 or infinity, i.e. P - Q must not be (0, 0) or the point at infinity
+    pub fn xadd(&self, p : &KummerPoint<F>, q : &KummerPoint<F>, p_minus_q : &KummerPoint<F>) -> KummerPoint<F> {
+        let u = (p.x.clone() - p.z.clone()) * (q.x.clone() + q.z.clone());
+        let v = (p.x.clone() + p.z.clone()) * (q.x.clone() - q.z.clone());
+        let (sum, difference) = (u.clone() + v.clone(), u - v);
+        KummerPoint::new(p_minus_q.z.clone() * sum.clone() * sum, p_minus_q.x.clone() * difference.clone() * difference)
+    }
+
+    /// (x([2]P), x(P + Q)) from x(P), x(Q) and x(P - Q) sharing the common terms
+    pub fn xdbladd(&self, p : &KummerPoint<F>, q : &KummerPoint<F>, p_minus_q : &KummerPoint<F>) -> (KummerPoint<F>, KummerPoint<F>) {
+        let (t0, t1) = (p.x.clone() + p.z.clone(), p.x.clone() - p.z.clone());
+        let (t2, t3) = (q.x.clone() + q.z.clone(), q.x.clone() - q.z.clone());
+        let (t0_sqr, t1_sqr) = (t0.clone() * t0.clone(), t1.clone() * t1.clone());
+
+        let (u, v) = (t1 * t2, t0 * t3);
+        let (sum, difference) = (u.clone() + v.clone(), u - v);
+        let p_plus_q = KummerPoint::new(p_minus_q.z.clone() * sum.clone() * sum, p_minus_q.x.clone() * difference.clone() * difference);
+
+        let e = t0_sqr.clone() - t1_sqr.clone();
+        let z = self.c24.clone() * t1_sqr;
+        let p_dbl = KummerPoint::new(z.clone() * t0_sqr, (z + self.a24_plus.clone() * e.clone()) * e);
+        (p_dbl, p_plus_q)
+    }
+
+    /// x([k]P) by the Montgomery ladder, x([-k]P) = x([k]P)
+    pub fn ladder(&self, p : &KummerPoint<F>, k : &BigInt) -> KummerPoint<F> {
+        if p.is_zero() || k.sign() == Sign::NoSign {
+            return self.zero();
+        }
+
+        // the differential addition fails for the difference (0, 0), which is of order 2
+        if p.x.is_zero() {
+            return match k.bit(0) {
+                true => p.clone(),
+                false => self.zero(),
+            };
+        }
+
+        // (r0, r1) = (x([m]P), x([m + 1]P)) for the leading bits m of k
+        let (mut r0, mut r1) = (self.zero(), p.clone());
+        for i in (0..k.bits()).rev() {
+            match k.magnitude().bit(i) {
+                true => (r1, r0) = self.xdbladd(&r1, &r0, p),
+                false => (r0, r1) = self.xdbladd(&r0, &r1, p),
+            }
+        }
+        r0
+    }
+
+    /// The 3-point ladder x(P + [k]Q) from x(P), x(Q) and x(P - Q) for k >= 0
+    /// The differences P - Q and P + [m]Q, ... of the intermediate steps must not be (0, 0)
+    pub fn ladder_3pt(&self, p : &KummerPoint<F>, q : &KummerPoint<F>, p_minus_q : &KummerPoint<F>, k : &BigInt) -> KummerPoint<F> {
+        assert!(k.sign() != Sign::Minus, "The scalar must be nonnegative");
+
+        // r0 = [2^i]Q, r1 = P + [k mod 2^i]Q and r2 = r1 - r0 up to sign
+        let (mut r0, mut r1, mut r2) = (q.clone(), p.clone(), p_minus_q.clone());
+        for i in 0..k.bits() {
+            match k.magnitude().bit(i) {
+                true => (r0, r1) = self.xdbladd(&r0, &r1, &r2),
+                false => (r0, r2) = self.xdbladd(&r0, &r2, &r1),
+            }
+        }
+        r1
+    }
+}
+
+impl<F> KummerPoint<F> where F: Field {
+    /// The point (x : z), they must not be both zero
+    pub fn new(x : F::Element, z : F::Element) -> Self {
+        assert!(!(x.is_zero() && z.is_zero()), "(0 : 0) is not a point");
+        KummerPoint { x, z }
+    }
+
+    /// The projective coordinates (X, Z)
+    pub fn coordinates(&self) -> (&F::Element, &F::Element) {
+        (&self.x, &self.z)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// The affine x = X / Z, None for the point at infinity
+    pub fn affine_x(&self) -> Option<F::Element> {
+        match self.is_zero() {
+            true => None,
+            false => Some(self.x.clone() * self.z.inv()),
+        }
+    }
+}
+
+impl<F> PartialEq for KummerPoint<F> where F: Field {
+    fn eq(&self, rhs : &Self) -> bool {
+        self.x.clone() * rhs.z.clone() == rhs.x.clone() * self.z.clone()
+    }
+}
+
+/// The reduced coordinates (x : 1) or (1 : 0)
+impl<F> fmt::Display for KummerPoint<F> where F: Field, F::Element: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.affine_x() {
+            Some(x) => write!(f, "({x} : 1)"),
+            None => write!(f, "(1 : 0)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::fp::FiniteField;
+    use crate::field::fp2::FiniteField2;
+    use crate::elliptic_curve::mont_curve::MontgomeryCurve;
+    use crate::group::Group;
+    use num::BigUint;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn kummer_test() {
+        let mut rng = StdRng::seed_from_u64(22);
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(430_u32));
+        let curve = MontgomeryCurve::new(&fp2, fp2.gen((&BigInt::from(3), &BigInt::from(6))));
+        let kummer = curve.kummer_line();
+
+        // the constants are projective
+        let lambda = fp2.gen((&BigInt::from(5), &BigInt::from(7)));
+        let (a24_plus, c24) = kummer.constants();
+        let scaled = KummerLine::new(&fp2, a24_plus.clone() * lambda.clone(), c24.clone() * lambda);
+
+        for _ in 0..10 {
+            let (p, q) = (curve.rand_with_rng(&mut rng), curve.rand_with_rng(&mut rng));
+            let (x_p, x_q) = (p.to_kummer(), q.to_kummer());
+            let x_p_minus_q = (p.clone() - q.clone()).to_kummer();
+            assert_eq!(kummer.xdbl(&x_p), (p.clone() + p.clone()).to_kummer());
+            assert_eq!(scaled.xdbl(&x_p), (p.clone() + p.clone()).to_kummer());
+            assert_eq!(kummer.xadd(&x_p, &x_q, &x_p_minus_q), (p.clone() + q.clone()).to_kummer());
+            assert_eq!(kummer.xdbladd(&x_p, &x_q, &x_p_minus_q),
+                ((p.clone() + p.clone()).to_kummer(), (p.clone() + q.clone()).to_kummer()));
+
+            let k = BigInt::from(rng.gen_range(0..100_000));
+            assert_eq!(kummer.ladder(&x_p, &k), (p.clone() * k.clone()).to_kummer());
+            assert_eq!(scaled.ladder(&x_p, &-k.clone()), (p.clone() * k.clone()).to_kummer());
+            assert_eq!(kummer.ladder_3pt(&x_p, &x_q, &x_p_minus_q, &k), (p.clone() + q.clone() * k.clone()).to_kummer());
+        }
+
+        // the point at infinity and the 2-torsion point (0, 0)
+        let x_p = curve.rand_with_rng(&mut rng).to_kummer();
+        assert!(kummer.ladder(&x_p, &BigInt::from(0)).is_zero());
+        assert_eq!(kummer.ladder(&x_p, &BigInt::from(1)), x_p);
+        assert!(kummer.ladder(&kummer.zero(), &BigInt::from(5)).is_zero());
+        assert_eq!(curve.zero().to_kummer(), kummer.zero());
+        let t = kummer.point(&fp2.zero());
+        assert!(kummer.xdbl(&t).is_zero());
+        assert_eq!(kummer.ladder(&t, &BigInt::from(7)), t);
+        assert!(kummer.ladder(&t, &BigInt::from(8)).is_zero());
+
+        // E(F_{431^2}) has exponent 432 for the supersingular curves
+        let supersingular = MontgomeryCurve::new(&fp2, fp2.gen((&BigInt::from(6), &BigInt::from(0))));
+        for _ in 0..10 {
+            let x_p = supersingular.rand_with_rng(&mut rng).to_kummer();
+            assert!(supersingular.kummer_line().ladder(&x_p, &BigInt::from(432)).is_zero());
+        }
+        assert_eq!(x_p.to_string(), format!("({} : 1)", x_p.affine_x().unwrap()));
+        assert_eq!(kummer.zero().to_string(), "(1 : 0)");
+    }
+}
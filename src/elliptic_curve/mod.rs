@@ -1 +1,98 @@
-pub mod mont_curve;
\ No newline at end of file
+//! Elliptic curves and their points
+use crate::field::FieldElement;
+use std::fmt;
+use std::ops::{Add, Neg};
+use num::BigInt;
+use num::bigint::Sign;
+
+pub mod mont_curve;
+pub mod kummer;
+pub mod projective;
+pub mod weierstrass;
+pub mod edwards;
+
+/// Error of the validated point constructors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PointError {
+    /// All the projective coordinates are zero
+    InvalidCoordinates,
+    /// The coordinates don't satisfy the curve equation
+    NotOnCurve,
+}
+
+impl fmt::Display for PointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PointError::InvalidCoordinates => write!(f, "the coordinates are all zero"),
+            PointError::NotOnCurve => write!(f, "the point is not on the curve"),
+        }
+    }
+}
+
+impl std::error::Error for PointError {}
+
+/// The sum of two affine points (x : y : z) with z != 0 on b y^2 z = x^3 + a2 x^2 z + a4 x z^2 + a6 z^3 without inversion
+/// The chord formula is used for distinct x-coordinates and the tangent one for P = Q, None is the point at infinity
+pub(crate) fn add_projective<T : FieldElement>(b : &T, a2 : &T, a4 : &T, (x1, y1, z1) : (&T, &T, &T), (x2, y2, z2) : (&T, &T, &T)) -> Option<(T, T, T)> {
+    let u = y2.clone() * z1.clone() - y1.clone() * z2.clone();
+    let v = x2.clone() * z1.clone() - x1.clone() * z2.clone();
+
+    if !v.is_zero() {
+        // lambda = u / v and x3 = T / (v^2 z1 z2) with T = (b u^2 - a2 v^2) z1 z2 - v^2 (x1 z2 + x2 z1)
+        let w = z1.clone() * z2.clone();
+        let v_sqr = v.clone() * v.clone();
+        let v_cube = v_sqr.clone() * v.clone();
+        let x1_z2 = x1.clone() * z2.clone();
+        let t = (b.clone() * u.clone() * u.clone() - a2.clone() * v_sqr.clone()) * w.clone() - v_sqr.clone() * (x1_z2.clone() + x2.clone() * z1.clone());
+        let y3 = u * (v_sqr * x1_z2 - t.clone()) - v_cube.clone() * y1.clone() * z2.clone();
+        return Some((v * t, y3, v_cube * w));
+    }
+
+    // P = -Q, including the doubling of the points of order 2
+    if !u.is_zero() || y1.is_zero() {
+        return None;
+    }
+
+    // lambda = t / s with t = 3x^2 + 2 a2 xz + a4 z^2 and s = 2byz, x3 = T / (s^2 z) with T = (b t^2 - a2 s^2) z - 2x s^2
+    let t = x1.clone() * x1.clone() * BigInt::from(3) + a2.clone() * x1.clone() * z1.clone() * BigInt::from(2) + a4.clone() * z1.clone() * z1.clone();
+    let s = b.clone() * y1.clone() * z1.clone() * BigInt::from(2);
+    let s_sqr = s.clone() * s.clone();
+    let s_cube = s_sqr.clone() * s.clone();
+    let t_x3 = (b.clone() * t.clone() * t.clone() - a2.clone() * s_sqr.clone()) * z1.clone() - x1.clone() * s_sqr.clone() * BigInt::from(2);
+    let y3 = t * (s_sqr * x1.clone() - t_x3.clone()) - s_cube.clone() * y1.clone();
+    Some((s * t_x3, y3, s_cube * z1.clone()))
+}
+
+/// Whether two projective points are equal, i.e. all the 2 x 2 minors of their coordinates vanish
+pub(crate) fn projective_eq<T : FieldElement>(p : &[&T], q : &[&T]) -> bool {
+    (0..p.len()).all(|i| (i + 1..p.len()).all(|j| p[i].clone() * q[j].clone() == q[i].clone() * p[j].clone()))
+}
+
+/// Scalar multiplication by double-and-add from the lowest bit, the doubling stops at the top bit of the scalar
+pub(crate) fn scalar_mul<P>(point : P, identity : P, scalar : &BigInt) -> P where P : Clone + Add<Output = P> + Neg<Output = P> {
+    let bits = scalar.bits();
+    let mut result = identity;
+    let mut power = point;
+    for i in 0..bits {
+        if scalar.magnitude().bit(i) {
+            result = result + power.clone();
+        }
+        if i + 1 < bits {
+            power = power.clone() + power;
+        }
+    }
+
+    match scalar.sign() {
+        Sign::Minus => -result,
+        _ => result,
+    }
+}
+
+/// All the affine points (x, y) of a curve over a small prime field, `point` validates the coordinates
+#[cfg(test)]
+pub(crate) fn affine_points<P, G>(fp : &crate::field::fp::FiniteField, point : G) -> Vec<P>
+    where G : Fn(&crate::field::fp::FiniteFieldElement, &crate::field::fp::FiniteFieldElement) -> Option<P> {
+    use crate::field::Field;
+    let elements : Vec<_> = num::range(BigInt::from(0), fp.characteristic()).map(|n| fp.gen(&n)).collect();
+    elements.iter().flat_map(|x| elements.iter().filter_map(|y| point(x, y)).collect::<Vec<_>>()).collect()
+}
@@ -1,51 +1,116 @@
 //! The elliptic curves of montgomery form
-use crate::field::{Field, FieldElement};
+use crate::field::{Field, FieldElement, FieldParsing, FieldEncoding, ElementEncoding, ConditionallySelectable, batch_inv, tonelli_shanks};
+use crate::field::extension::ExtensionField;
+use crate::field::hash_to_field::{self, HashToField};
 use crate::group::{Group, GroupElement};
 use std::ops::{Neg, Mul};
-use num::BigInt;
+use num::{BigInt, Zero, One};
 use impl_ops::impl_bin_ops;
-use num::bigint::Sign;
+use subtle::Choice;
+use rand::RngCore;
+use crate::encoding::{self, DecodeError, Decoder};
+use crate::sage::{self, ParseError};
+use crate::poly::Polynomial;
+use super::kummer::{KummerLine, KummerPoint};
+use super::projective::ProjectiveMontgomeryCurve;
+use super::PointError;
+use super::edwards::add_completed;
+use std::fmt;
+use std::sync::Arc;
+use sha2::digest::{Digest, core_api::BlockSizeUser};
 
-/// The structure of a montgomery curve
+/// The structure of a montgomery curve B * y^2 = x^3 + A * x^2 + x
+/// It's a cheap handle to the shared coefficients, so the points own a copy of it
 #[derive(Clone, Debug)]
-pub struct MontgomeryCurve<'a, F> where F: Field<'a> + 'a {
-    field : &'a F,
-    A : F::Element,
+pub struct MontgomeryCurve<F> where F: Field {
+    field : F,
+    coefficients : Arc<Coefficients<F::Element>>,
 }
 
-impl<'a, F> MontgomeryCurve<'a, F> where F: Field<'a> + 'a {
-    pub fn new(field : &'a F, A : F::Element) -> Self {
+/// The coefficients A and B of a montgomery curve
+#[derive(Debug)]
+#[allow(non_snake_case)]
+struct Coefficients<T> {
+    A : T,
+    B : T,
+}
+
+impl<F> MontgomeryCurve<F> where F: Field {
+    /// The curve y^2 = x^3 + A * x^2 + x with B = 1
+    #[allow(non_snake_case)]
+    pub fn new(field : &F, A : F::Element) -> Self {
+        Self::new_with_b(field, A, field.one())
+    }
+
+    /// The curve B * y^2 = x^3 + A * x^2 + x, a quadratic twist of the one with B = 1 when B isn't a square
+    #[allow(non_snake_case)]
+    pub fn new_with_b(field : &F, A : F::Element, B : F::Element) -> Self {
+        assert!(!B.is_zero(), "The coefficient B must be nonzero");
         MontgomeryCurve {
-            field,
-            A,
+            field : field.clone(),
+            coefficients : Arc::new(Coefficients { A, B }),
         }
     }
 
-    pub fn gen(&'a self, (x,y,z) : (&F::Element, &F::Element, &F::Element)) -> MontgomeryCurvePoint<'a, F> {
+    /// The point (x : y : z) without any check, see [point](Self::point) for the validated one
+    pub fn gen(&self, (x,y,z) : (&F::Element, &F::Element, &F::Element)) -> MontgomeryCurvePoint<F> {
         MontgomeryCurvePoint {
-            curve : self,
+            curve : self.clone(),
             x: x.clone(), y: y.clone(), z: z.clone(),
         }
     }
 
-    pub fn j_invariant(&'a self) -> F::Element {
-        (self.A.clone() * self.A.clone() - self.field.one() * BigInt::from(3)).pow(&BigInt::from(3)) * BigInt::from(256) * 
-        (self.A.clone() * self.A.clone() - self.field.one() * BigInt::from(4)).inv()
+    /// The point (x : y : z) if it's a projective point on the curve
+    pub fn point(&self, coordinates : (&F::Element, &F::Element, &F::Element)) -> Result<MontgomeryCurvePoint<F>, PointError> {
+        let point = self.gen(coordinates);
+        if point.x.is_zero() && point.y.is_zero() && point.z.is_zero() {
+            return Err(PointError::InvalidCoordinates);
+        }
+        match point.is_on_curve() {
+            true => Ok(point),
+            false => Err(PointError::NotOnCurve),
+        }
+    }
+
+    /// The field of definition
+    pub fn field(&self) -> &F {
+        &self.field
+    }
+
+    /// The coefficient A
+    pub fn coefficient(&self) -> &F::Element {
+        &self.coefficients.A
     }
 
-    /// Generate a nonzero random point
-    pub fn rand(&'a self) -> MontgomeryCurvePoint<'a, F> {
+    /// The coefficients (A, B)
+    pub fn coefficients(&self) -> (&F::Element, &F::Element) {
+        (&self.coefficients.A, &self.coefficients.B)
+    }
+
+    /// The right hand side (x^3 + A * x^2 + x) / B of y^2
+    fn rhs(&self, x : &F::Element) -> F::Element {
+        (x.clone() * x.clone() + self.coefficients.A.clone() * x.clone() + self.field.one()) * x.clone() * self.coefficients.B.inv()
+    }
+
+    /// It doesn't depend on B
+    pub fn j_invariant(&self) -> F::Element {
+        (self.coefficients.A.clone() * self.coefficients.A.clone() - self.field.one() * BigInt::from(3)).pow(&BigInt::from(3)) * BigInt::from(256) * 
+        (self.coefficients.A.clone() * self.coefficients.A.clone() - self.field.one() * BigInt::from(4)).inv()
+    }
+
+    /// Generate a nonzero random point using the thread-local random number generator
+    pub fn rand(&self) -> MontgomeryCurvePoint<F> {
+        self.rand_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Generate a nonzero random point using the given random number generator
+    pub fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> MontgomeryCurvePoint<F> {
         let mut x : F::Element;
         let y : F::Element;
         loop {
-            x = self.field.rand(None);
-
-            // y^2 = x^3 + A * x^2 + x
-            let y_sqr = x.clone().pow(&BigInt::from(3)) + 
-            self.A.clone() * x.clone().pow(&BigInt::from(2)) +
-            x.clone();
+            x = self.field.rand_with_rng(rng, None);
 
-            y = match y_sqr.sqrt(){
+            y = match self.rhs(&x).sqrt_with_rng(rng) {
                 Some(y) => y,
                 None => continue,
             };
@@ -53,15 +118,35 @@ impl<'a, F> MontgomeryCurve<'a, F> where F: Field<'a> + 'a {
             break       
         }
 
-        MontgomeryCurvePoint { curve: self, x, y, z: self.field.one() }
+        self.gen((&x, &y, &self.field.one()))
+    }
+
+    /// The Kummer line for the x-only arithmetic with the constants (A + 2 : 4), which doesn't depend on B
+    pub fn kummer_line(&self) -> KummerLine<F> {
+        KummerLine::new(&self.field, self.coefficients.A.clone() + self.field.one() * BigInt::from(2), self.field.one() * BigInt::from(4))
+    }
+
+    /// The projective coefficient (A : 1) with the same B
+    pub fn to_projective(&self) -> ProjectiveMontgomeryCurve<F> {
+        ProjectiveMontgomeryCurve::new_with_b(&self.field, self.coefficients.A.clone(), self.field.one(), self.coefficients.B.clone())
+    }
+
+    /// The same curve over an extension of its field
+    pub fn base_change(&self, field : &ExtensionField<F>) -> MontgomeryCurve<ExtensionField<F>> {
+        MontgomeryCurve::new_with_b(field, field.embed(&self.coefficients.A), field.embed(&self.coefficients.B))
     }
 
     /// Probabilistic algorithm to check if it's supersingular
     /// The default number of test cases is 10
-    pub fn is_supersingular(&'a self) -> bool {
+    pub fn is_supersingular(&self) -> bool {
+        self.is_supersingular_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Probabilistic algorithm to check if it's supersingular using the given random number generator
+    pub fn is_supersingular_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> bool {
         let order : BigInt = self.field.characteristic() + 1;
-        for _ in [0..10] {
-            let point = self.rand();
+        for _ in 0..10 {
+            let point = self.rand_with_rng(rng);
             let point_q = point * order.clone();
             if !point_q.is_zero() {
                 return false
@@ -72,137 +157,1035 @@ impl<'a, F> MontgomeryCurve<'a, F> where F: Field<'a> + 'a {
     }
 }
 
-impl<'a, F> Group<'a> for MontgomeryCurve<'a, F> where F: Field<'a> + 'a {
-    type Element = MontgomeryCurvePoint<'a, F>;
+impl<F> MontgomeryCurve<F> where F: FieldParsing {
+    /// Parse a curve in the format of its Display, the field after "over" is ignored
+    pub fn from_str(field : &F, s : &str) -> Result<Self, ParseError> {
+        let equation = s.trim().strip_prefix("Elliptic Curve defined by").ok_or(ParseError::InvalidSyntax)?;
+        let equation = equation.split(" over ").next().unwrap();
+        let (lhs, rhs) = equation.split_once('=').ok_or(ParseError::InvalidSyntax)?;
+
+        // B*y^2 = x^3 + A*x^2 + x
+        let lhs = Polynomial::parse_in(field, lhs, "y")?;
+        let rhs = Polynomial::from_str(field, rhs)?;
+        let (zero, one) = (field.zero(), field.one());
+        let b = match lhs.coefficients() {
+            [c0, c1, b] if *c0 == zero && *c1 == zero => b.clone(),
+            _ => return Err(ParseError::InvalidSyntax),
+        };
+        let a = match rhs.coefficients() {
+            [c0, c1, a, c3] if *c0 == zero && *c1 == one && *c3 == one => a.clone(),
+            _ => return Err(ParseError::InvalidSyntax),
+        };
+        match (a.clone() * a.clone() - field.one() * BigInt::from(4)).is_zero() {
+            true => Err(ParseError::SingularCurve),
+            false => Ok(MontgomeryCurve::new_with_b(field, a, b)),
+        }
+    }
+
+    /// Parse a point (x : y : z) on the curve
+    /// It rejects the points which are not on the curve
+    pub fn point_from_str(&self, s : &str) -> Result<MontgomeryCurvePoint<F>, ParseError> {
+        let s = s.trim().strip_prefix('(').and_then(|s| s.strip_suffix(')')).ok_or(ParseError::InvalidSyntax)?;
+        let coordinates = s.split(':').map(|c| self.field.element_from_str(c)).collect::<Result<Vec<_>, _>>()?;
+        let [x, y, z] : [F::Element; 3] = coordinates.try_into().map_err(|_| ParseError::InvalidSyntax)?;
+        self.point((&x, &y, &z)).map_err(|e| match e {
+            PointError::InvalidCoordinates => ParseError::InvalidSyntax,
+            PointError::NotOnCurve => ParseError::NotOnCurve,
+        })
+    }
+}
+
+impl<F> MontgomeryCurve<F> where F: FieldEncoding {
+    /// The canonical encoding of the coefficient A, followed by B when B != 1
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self.coefficients.B == self.field.one() {
+            true => self.coefficients.A.to_bytes(),
+            false => [self.coefficients.A.to_bytes(), self.coefficients.B.to_bytes()].concat(),
+        }
+    }
+
+    /// The hexadecimal string of the canonical encoding
+    pub fn to_hex(&self) -> String {
+        encoding::encode_hex(&self.to_bytes())
+    }
+
+    /// Decode a curve from the encoding of its coefficients A and B, see [to_bytes](Self::to_bytes)
+    /// It rejects the singular curves A^2 = 4 or B = 0, and B = 1 given explicitly
+    pub fn from_bytes(field : &F, bytes : &[u8]) -> Result<Self, DecodeError> {
+        let len = field.byte_len();
+        let (a, b) = match bytes.len() == 2 * len {
+            true => (field.element_from_bytes(&bytes[..len])?, field.element_from_bytes(&bytes[len..])?),
+            false => (field.element_from_bytes(bytes)?, field.one()),
+        };
+        if bytes.len() == 2 * len && b == field.one() {
+            return Err(DecodeError::NonCanonical);
+        }
+        match (a.clone() * a.clone() - field.one() * BigInt::from(4)).is_zero() || b.is_zero() {
+            true => Err(DecodeError::SingularCurve),
+            false => Ok(MontgomeryCurve::new_with_b(field, a, b)),
+        }
+    }
+
+    /// Decode a curve from the hexadecimal string of its encoding
+    pub fn from_hex(field : &F, hex : &str) -> Result<Self, DecodeError> {
+        Self::from_bytes(field, &encoding::decode_hex(hex)?)
+    }
+
+    /// Decode a point on the curve, see [to_bytes](MontgomeryCurvePoint::to_bytes)
+    /// It rejects the affine points which are not on the curve
+    pub fn point_from_bytes(&self, bytes : &[u8]) -> Result<MontgomeryCurvePoint<F>, DecodeError> {
+        let len = self.field.byte_len();
+        encoding::check_len(bytes, 1 + 2 * len)?;
+
+        match bytes[0] {
+            0 if bytes[1..].iter().all(|byte| *byte == 0) => Ok(self.zero()),
+            1 => {
+                let x = self.field.element_from_bytes(&bytes[1..1 + len])?;
+                let y = self.field.element_from_bytes(&bytes[1 + len..])?;
+
+                // B * y^2 = x^3 + A * x^2 + x
+                let rhs = (x.clone() * x.clone() + self.coefficients.A.clone() * x.clone() + self.field.one()) * x.clone();
+                match self.coefficients.B.clone() * y.clone() * y.clone() == rhs {
+                    true => Ok(self.gen((&x, &y, &self.field.one()))),
+                    false => Err(DecodeError::NotOnCurve),
+                }
+            },
+            _ => Err(DecodeError::NonCanonical),
+        }
+    }
+
+    /// Decode a point on the curve from the hexadecimal string of its encoding
+    pub fn point_from_hex(&self, hex : &str) -> Result<MontgomeryCurvePoint<F>, DecodeError> {
+        self.point_from_bytes(&encoding::decode_hex(hex)?)
+    }
+}
+
+impl<F> MontgomeryCurve<F> where F: HashToField {
+    /// The non-square Z of Elligator 2 : the first of ±c, ±(c + 1), ... which isn't a square
+    /// where c is 1 over a prime field and the generator i over F_{p^2}
+    fn elligator2_z(&self) -> F::Element {
+        let mut c = match self.field.extension_degree() {
+            1 => self.field.one(),
+            m => {
+                let mut coordinates = vec![BigInt::zero(); m];
+                coordinates[1] = BigInt::one();
+                self.field.element_from_coordinates(&coordinates)
+            },
+        };
+        loop {
+            for z in [c.clone(), -c.clone()] {
+                if !z.is_square() {
+                    return z;
+                }
+            }
+            c = c + self.field.one();
+        }
+    }
+
+    /// The Z of the Shallue-van de Woestijne method for y^2 = x^3 + B^2 * x (RFC 9380, appendix H.1)
+    fn svdw_z(&self) -> F::Element {
+        let one = self.field.one();
+        let mut c = one.clone();
+        loop {
+            for z in [c.clone(), -c.clone()] {
+                // h(Z) = -(3 * Z^2 + 4a) / (4 * g(Z)) for g(x) = x^3 + a * x with a = B^2
+                let gz = self.svdw_rhs(&z);
+                let t = z.clone() * z.clone() * BigInt::from(3) + self.coefficients.B.clone() * self.coefficients.B.clone() * BigInt::from(4);
+                if gz.is_zero() || t.is_zero() {
+                    continue;
+                }
+                let h = -t * (gz.clone() * BigInt::from(4)).inv();
+                let half_z = -z.clone() * (one.clone() * BigInt::from(2)).inv();
+                if h.is_square() && (gz.is_square() || self.svdw_rhs(&half_z).is_square()) {
+                    return z;
+                }
+            }
+            c = c + one.clone();
+        }
+    }
+
+    /// The curve y^2 = x^3 + B^2 * x isomorphic to B * y^2 = x^3 + x by (x, y) -> (Bx, B^2 y)
+    fn svdw_rhs(&self, x : &F::Element) -> F::Element {
+        (x.clone() * x.clone() + self.coefficients.B.clone() * self.coefficients.B.clone()) * x.clone()
+    }
+
+    /// The square root by Tonelli-Shanks with the non-square of Elligator 2, so the maps don't draw random numbers
+    fn sqrt(&self, a : &F::Element, non_square : &F::Element) -> F::Element {
+        tonelli_shanks(a, non_square, &self.field.order()).expect("The maps only take the square roots of squares")
+    }
+
+    /// Map a field element to a point with Elligator 2 (RFC 9380, section 6.7.1)
+    /// The curves B * y^2 = x^3 + x with A = 0 are mapped with the Shallue-van de Woestijne method (RFC 9380, section 6.6.1)
+    /// instead since Elligator 2 would send every element to (0, 0).
+    ///
+    /// The RFC also requires A^2 - 4 to be a non-square, i.e. (0, 0) to be the only rational point of order 2.
+    /// This never holds for the supersingular curves over F_{p^2} with the Frobenius ±p, such as the curves of SIDH,
+    /// because their whole 2-torsion is rational. The output is still on the curve since g(x2) = Z * u^2 * g(x1),
+    /// but the map can also hit the other points of order 2 and the analysis of the RFC doesn't cover it.
+    pub fn map_to_curve(&self, u : &F::Element) -> MontgomeryCurvePoint<F> {
+        let one = self.field.one();
+        let z = self.elligator2_z();
+        if self.coefficients.A.is_zero() {
+            return self.map_to_curve_svdw(u, &z);
+        }
+
+        // x1 = -A / (1 + Z * u^2), or -A if the denominator is zero
+        let denominator = one.clone() + z.clone() * u.clone() * u.clone();
+        let x1 = match denominator.is_zero() {
+            true => -self.coefficients.A.clone(),
+            false => -self.coefficients.A.clone() * denominator.inv(),
+        };
+        let x2 = -x1.clone() - self.coefficients.A.clone();
+
+        let gx1 = self.rhs(&x1);
+        let (x, y_sqr, sign) = match gx1.is_square() {
+            true => (x1, gx1, true),
+            false => (x2.clone(), self.rhs(&x2), false),
+        };
+
+        let mut y = self.sqrt(&y_sqr, &z);
+        if hash_to_field::sgn0(&self.field, &y) != sign {
+            y = -y;
+        }
+        self.gen((&x, &y, &one))
+    }
+
+    /// The Shallue-van de Woestijne method for y^2 = x^3 + B^2 * x with the constants c1, ..., c4 of the RFC,
+    /// the point (x, y) is then sent to (x / B, y / B^2) on B * y^2 = x^3 + x
+    fn map_to_curve_svdw(&self, u : &F::Element, non_square : &F::Element) -> MontgomeryCurvePoint<F> {
+        let one = self.field.one();
+        let z = self.svdw_z();
+        let b_sqr = self.coefficients.B.clone() * self.coefficients.B.clone();
+        let c1 = self.svdw_rhs(&z);
+        let c2 = -z.clone() * (one.clone() * BigInt::from(2)).inv();
+        let t = z.clone() * z.clone() * BigInt::from(3) + b_sqr.clone() * BigInt::from(4);
+        let mut c3 = self.sqrt(&(-c1.clone() * t.clone()), non_square);
+        if hash_to_field::sgn0(&self.field, &c3) {
+            c3 = -c3;
+        }
+        let c4 = -c1.clone() * BigInt::from(4) * t.inv();
+
+        let tv1 = u.clone() * u.clone() * c1;
+        let tv2 = one.clone() + tv1.clone();
+        let tv1 = one.clone() - tv1;
+        // inv0 sends zero to zero
+        let tv3 = match (tv1.clone() * tv2.clone()).is_zero() {
+            true => self.field.zero(),
+            false => (tv1.clone() * tv2.clone()).inv(),
+        };
+        let tv4 = u.clone() * tv1 * tv3.clone() * c3;
+        let x1 = c2.clone() - tv4.clone();
+        let x2 = c2 + tv4;
+        let x3 = tv2.clone() * tv2 * tv3;
+        let x3 = x3.clone() * x3 * c4 + z;
+
+        let x = [x1, x2].into_iter().find(|x| self.svdw_rhs(x).is_square()).unwrap_or(x3);
+        let mut y = self.sqrt(&self.svdw_rhs(&x), non_square);
+        if hash_to_field::sgn0(&self.field, u) != hash_to_field::sgn0(&self.field, &y) {
+            y = -y;
+        }
+        self.gen((&(x * self.coefficients.B.clone()), &y, &b_sqr))
+    }
+
+    /// The nonuniform encoding encode_to_curve of RFC 9380 : map_to_curve(hash_to_field(msg, 1))
+    pub fn encode_to_curve<H : Digest + BlockSizeUser>(&self, msg : &[u8], dst : &[u8]) -> MontgomeryCurvePoint<F> {
+        let u = hash_to_field::hash_to_field::<F, H>(&self.field, msg, dst, 1);
+        self.map_to_curve(&u[0])
+    }
+
+    /// The random oracle hash_to_curve of RFC 9380 : the sum of the maps of two field elements
+    /// The cofactor isn't cleared, multiply the output by it to land in the prime-order subgroup
+    pub fn hash_to_curve<H : Digest + BlockSizeUser>(&self, msg : &[u8], dst : &[u8]) -> MontgomeryCurvePoint<F> {
+        let u = hash_to_field::hash_to_field::<F, H>(&self.field, msg, dst, 2);
+        self.map_to_curve(&u[0]) + self.map_to_curve(&u[1])
+    }
+}
+
+/// Division polynomials and the n-torsion subgroup
+/// They're polynomials in x : f_n = ψ_n for odd n and f_n = ψ_n / ψ_2 = ψ_n / (2y) for even n
+/// They're the ones of y^2 = x^3 + A * x^2 + x, the twist by B has the same x-coordinates of the torsion points
+impl<F> MontgomeryCurve<F> where F: Field {
+    /// The right hand side x^3 + A * x^2 + x of the curve equation
+    fn rhs_polynomial(&self) -> Polynomial<F> {
+        Polynomial::new(&self.field, vec![self.field.zero(), self.field.one(), self.coefficients.A.clone(), self.field.one()])
+    }
+
+    /// f_0, ..., f_n by the recursive formulas
+    /// f_{2m+1} = F^2 f_{m+2} f_m^3 - f_{m-1} f_{m+1}^3 for even m, f_{m+2} f_m^3 - F^2 f_{m-1} f_{m+1}^3 for odd m
+    /// f_{2m} = f_m (f_{m+2} f_{m-1}^2 - f_{m-2} f_{m+1}^2) where F = ψ_2^2 = 4(x^3 + A * x^2 + x)
+    fn division_polynomials(&self, n : usize) -> Vec<Polynomial<F>> {
+        let field = &self.field;
+        let c = |n : i64| field.one() * BigInt::from(n);
+        let a = self.coefficients.A.clone();
+        let f_sqr = {
+            let f = self.rhs_polynomial() * BigInt::from(4);
+            f.clone() * f
+        };
+
+        // f_3 = 3x^4 + b_2 x^3 + 3b_4 x^2 + 3b_6 x + b_8 and f_4 = 2x^6 + b_2 x^5 + 5b_4 x^4 + 10b_6 x^3 + 10b_8 x^2 + (b_2 b_8 - b_4 b_6) x + b_4 b_8 - b_6^2
+        // with b_2 = 4A, b_4 = 2, b_6 = 0 and b_8 = -1
+        let mut f = vec![
+            Polynomial::new(field, vec![]),
+            Polynomial::new(field, vec![c(1)]),
+            Polynomial::new(field, vec![c(1)]),
+            Polynomial::new(field, vec![c(-1), c(0), c(6), a.clone() * BigInt::from(4), c(3)]),
+            Polynomial::new(field, vec![c(-2), -a.clone() * BigInt::from(4), c(-10), c(0), c(10), a * BigInt::from(4), c(2)]),
+        ];
+        for k in 5..=n {
+            let m = k / 2;
+            let next = match (k % 2, m % 2) {
+                (1, 0) => f_sqr.clone() * f[m + 2].clone() * f[m].clone() * f[m].clone() * f[m].clone()
+                    - f[m - 1].clone() * f[m + 1].clone() * f[m + 1].clone() * f[m + 1].clone(),
+                (1, _) => f[m + 2].clone() * f[m].clone() * f[m].clone() * f[m].clone()
+                    - f_sqr.clone() * f[m - 1].clone() * f[m + 1].clone() * f[m + 1].clone() * f[m + 1].clone(),
+                _ => f[m].clone() * (f[m + 2].clone() * f[m - 1].clone() * f[m - 1].clone()
+                    - f[m - 2].clone() * f[m + 1].clone() * f[m + 1].clone()),
+            };
+            f.push(next);
+        }
+        f.truncate(n + 1);
+        f
+    }
+
+    /// The division polynomial f_n in x, ψ_n for odd n and ψ_n / (2y) for even n
+    pub fn division_polynomial(&self, n : usize) -> Polynomial<F> {
+        self.division_polynomials(n).pop().unwrap()
+    }
+
+    /// The monic polynomial whose roots are the x-coordinates of the nonzero n-torsion points, each once
+    /// It's f_n for odd n and f_n * (x^3 + A * x^2 + x) for even n, which is squarefree when the characteristic doesn't divide n
+    pub fn torsion_polynomial(&self, n : usize) -> Polynomial<F> {
+        assert!(n >= 1, "The torsion is defined for n >= 1");
+        let f = self.division_polynomial(n);
+        match n % 2 {
+            0 => (f * self.rhs_polynomial()).monic(),
+            _ => f.monic(),
+        }
+    }
+
+    /// The degree k of the smallest extension F_{q^k} of the field F_q over which all of E[n] is defined
+    /// The characteristic must not divide n
+    pub fn torsion_field_degree(&self, n : usize) -> usize {
+        self.torsion_field_degree_with_rng(n, &mut rand::thread_rng())
+    }
+
+    /// The degree of the field of definition of E[n] using the given random number generator
+    /// The point (x, y) with x a root of an irreducible factor h of degree d is defined over F_{q^d}
+    /// when (x^3 + A * x^2 + x) / B is a square in F_q[x] / (h), and over F_{q^2d} otherwise
+    pub fn torsion_field_degree_with_rng<R : RngCore + ?Sized>(&self, n : usize, rng : &mut R) -> usize {
+        assert!(!(BigInt::from(n) % self.field.characteristic()).is_zero(), "The characteristic must not divide n");
+        let rhs = self.rhs_polynomial();
+        self.torsion_polynomial(n).factor_with_rng(rng).into_iter().fold(1, |k, (h, _)| {
+            let extension = ExtensionField::new(&self.field, &h);
+            let y_sqr = rhs.eval_extension(&extension, &extension.generator()) * extension.embed(&self.coefficients.B.inv());
+            let d = match y_sqr.is_square() {
+                true => h.degree(),
+                false => 2 * h.degree(),
+            };
+            num::integer::lcm(k, d)
+        })
+    }
+
+    /// All the n-torsion points over the field including the point at infinity
+    pub fn torsion_points(&self, n : usize) -> Vec<MontgomeryCurvePoint<F>> {
+        self.torsion_points_with_rng(n, &mut rand::thread_rng())
+    }
+
+    /// All the n-torsion points over the field using the given random number generator
+    pub fn torsion_points_with_rng<R : RngCore + ?Sized>(&self, n : usize, rng : &mut R) -> Vec<MontgomeryCurvePoint<F>> {
+        let one = self.field.one();
+        let mut points = vec![self.zero()];
+        for x in self.torsion_polynomial(n).roots_with_rng(rng) {
+            let y_sqr = self.rhs(&x);
+            if y_sqr.is_zero() {
+                points.push(self.gen((&x, &y_sqr, &one)));
+            } else if let Some(y) = y_sqr.sqrt_with_rng(rng) {
+                points.push(self.gen((&x, &y, &one)));
+                points.push(self.gen((&x, &-y, &one)));
+            }
+        }
+        points
+    }
+}
+
+/// The description of Sage, e.g. `Elliptic Curve defined by y^2 = x^3 + 6*x^2 + x over Finite Field of size 431`
+/// and `Elliptic Curve defined by 5*y^2 = x^3 + 6*x^2 + x over Finite Field of size 431` for B = 5
+impl<F> fmt::Display for MontgomeryCurve<F> where F: Field + fmt::Display, F::Element: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let one = self.field.one().to_string();
+        write!(f, "Elliptic Curve defined by ")?;
+        sage::write_sum(f, &[(self.coefficients.B.to_string(), "y^2".to_string())])?;
+        write!(f, " = ")?;
+        sage::write_sum(f, &[(one.clone(), "x^3".to_string()), (self.coefficients.A.to_string(), "x^2".to_string()), (one, "x".to_string())])?;
+        write!(f, " over {}", self.field)
+    }
+}
+
+/// Curves decode their points
+impl<F> Decoder for MontgomeryCurve<F> where F: FieldEncoding {
+    type Output = MontgomeryCurvePoint<F>;
+    fn decode(&self, bytes : &[u8]) -> Result<Self::Output, DecodeError> {
+        self.point_from_bytes(bytes)
+    }
+}
+
+impl<F> Group for MontgomeryCurve<F> where F: Field {
+    type Element = MontgomeryCurvePoint<F>;
     /// Implement DynZero for [FiniteField](FiniteField)
-    fn zero(&'a self) -> Self::Element {
+    fn zero(&self) -> Self::Element {
         self.gen((&self.field.zero(), &self.field.one(), &self.field.zero()))
     }
 }
 
 /// The structure of a (projective coordinate)point of a montgomery curve
+/// The coefficients of the curve are shared by all the points derived from it, so the group operations don't copy them
 #[derive(Debug, Clone)]
-pub struct MontgomeryCurvePoint<'a, F> where F: Field<'a> + 'a {
-    curve : &'a MontgomeryCurve<'a, F>,
+pub struct MontgomeryCurvePoint<F> where F: Field {
+    curve : MontgomeryCurve<F>,
     x : F::Element,
     y : F::Element,
     z : F::Element,
 }
 
-impl<'a, F> MontgomeryCurvePoint<'a, F> where F: Field<'a> + 'a {
+impl<F> MontgomeryCurvePoint<F> where F: Field {
+    /// The point (x : y : z) on the same curve sharing its handle
+    fn with_coordinates(&self, (x, y, z) : (&F::Element, &F::Element, &F::Element)) -> Self {
+        MontgomeryCurvePoint {
+            curve : self.curve.clone(),
+            x : x.clone(), y : y.clone(), z : z.clone(),
+        }
+    }
+
+    /// The point at infinity of the same curve
+    fn identity(&self) -> Self {
+        let field = &self.curve.field;
+        self.with_coordinates((&field.zero(), &field.one(), &field.zero()))
+    }
+
+    /// The coordinate which becomes one after the reduction
+    fn reduce_coordinate(&self) -> &F::Element {
+        match self.z.is_zero() {
+            true => &self.y,
+            false => &self.z,
+        }
+    }
+
+    fn scale(&mut self, lambda : &F::Element) {
+        self.x = self.x.clone() * lambda.clone();
+        self.y = self.y.clone() * lambda.clone();
+        self.z = self.z.clone() * lambda.clone();
+    }
+
     fn reduce(&mut self) {
-        let lambda_inv = match self.z.is_zero() {
-            true => self.y.clone().inv(),
-            false => self.z.clone().inv(),
-        };
+        let lambda_inv = self.reduce_coordinate().inv();
+        self.scale(&lambda_inv);
+    }
 
-        self.x = self.x.clone() *lambda_inv.clone();
-        self.y = self.y.clone() *lambda_inv.clone();
-        self.z = self.z.clone() *lambda_inv.clone();
+    /// The same point on the curve over an extension of its field
+    pub fn base_change(&self, field : &ExtensionField<F>) -> MontgomeryCurvePoint<ExtensionField<F>> {
+        self.curve.base_change(field).gen((&field.embed(&self.x), &field.embed(&self.y), &field.embed(&self.z)))
     }
-}
 
-impl<'a, F> GroupElement for MontgomeryCurvePoint<'a, F> where F: Field<'a> + 'a {
-    fn is_zero(&self) -> bool {
-        self.z.is_zero()
+    /// The projective coordinates (x, y, z)
+    pub fn coordinates(&self) -> (&F::Element, &F::Element, &F::Element) {
+        (&self.x, &self.y, &self.z)
     }
-}
 
-impl<'a, F> PartialEq for MontgomeryCurvePoint<'a, F> where F: Field<'a> + 'a {
-    fn eq(&self, rhs : &Self) -> bool {
-        if !self.x.is_zero() {
-            let lambda = self.x.inv().clone() * rhs.x.clone();
-            return self.y.clone() * lambda.clone() == rhs.y && self.z.clone() * lambda == rhs.z;
+    /// Whether it satisfies B y^2 z = x^3 + A x^2 z + x z^2
+    pub fn is_on_curve(&self) -> bool {
+        let (x, y, z) = (&self.x, &self.y, &self.z);
+        let lhs = self.curve.coefficients.B.clone() * y.clone() * y.clone() * z.clone();
+        let rhs = (x.clone() * x.clone() + self.curve.coefficients.A.clone() * x.clone() * z.clone() + z.clone() * z.clone()) * x.clone();
+        lhs == rhs
+    }
+
+    /// The x-only point (x : z), which is (1 : 0) for the point at infinity
+    pub fn to_kummer(&self) -> KummerPoint<F> {
+        match self.is_zero() {
+            true => KummerPoint::new(self.curve.field.one(), self.curve.field.zero()),
+            false => KummerPoint::new(self.x.clone(), self.z.clone()),
         }
+    }
 
-        if !self.y.is_zero() {
-            let lambda = self.y.inv().clone() * rhs.y.clone();
-            return self.x.clone() * lambda.clone() == rhs.x && self.z.clone() * lambda == rhs.z;
+    /// Reduce all the points using a single inversion
+    pub fn batch_reduce(points : &mut [Self]) {
+        let lambdas : Vec<F::Element> = points.iter().map(|point| point.reduce_coordinate().clone()).collect();
+        for (point, lambda_inv) in points.iter_mut().zip(batch_inv(&lambdas)) {
+            point.scale(&lambda_inv);
         }
-        
-        let lambda = self.z.inv().clone() * rhs.z.clone();
-        self.x.clone() * lambda.clone() == rhs.x && self.y.clone() * lambda == rhs.y
     }
 }
 
-impl<'a, F> Neg for MontgomeryCurvePoint<'a, F> where F: Field<'a> + 'a {
-    type Output = Self;
-    fn neg(self) -> Self {
-        if self.z.is_zero() {
-            return self;
+impl<F> MontgomeryCurvePoint<F> where F: FieldEncoding {
+    /// The canonical encoding : 0x00 followed by zeros for the point at infinity,
+    /// 0x01 || x || y for the affine point (x, y)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let len = self.curve.field.byte_len();
+        if self.is_zero() {
+            return vec![0; 1 + 2 * len];
         }
 
-        let mut result = self.curve.gen((&self.x, &-self.y.clone(), &self.z));
-        result.reduce();
-        result
+        let mut point = self.clone();
+        point.reduce();
+        [vec![1], point.x.to_bytes(), point.y.to_bytes()].concat()
+    }
+
+    /// The hexadecimal string of the canonical encoding
+    pub fn to_hex(&self) -> String {
+        encoding::encode_hex(&self.to_bytes())
     }
 }
 
-#[impl_bin_ops]
-impl<'a, F> Add for MontgomeryCurvePoint<'a, F> where F: Field<'a> + 'a {
-    fn add(self, rhs : MontgomeryCurvePoint<'a, F>) -> MontgomeryCurvePoint<'a, F> {
+impl<F> MontgomeryCurvePoint<F> where F: Field, F::Element: ConditionallySelectable {
+    /// Constant-time scalar multiplication on the lowest bits of a little-endian scalar
+    /// It uses the x-only Montgomery ladder and the Okeya-Sakurai y-coordinate recovery,
+    /// so it always runs the same field operations for a fixed number of bits
+    /// The recovery degenerates for [k]P = O, [k + 1]P = O and the points of order 2,
+    /// those results are put in place with constant-time selections
+    pub fn mul_ct(&self, scalar : &[u64], bits : usize) -> Self {
         if self.is_zero() {
-            return rhs.clone();
-        }
-
-        if rhs.is_zero() {
             return self.clone();
         }
 
-        if self.clone() == -rhs.clone() {
-            return self.curve.zero();
+        let field = &self.curve.field;
+        let a = &self.curve.coefficients.A;
+        let mut point = self.clone();
+        point.reduce();
+        let (x_p, y_p) = (point.x, point.y);
+        let (zero, one) = (field.zero(), field.one());
+
+        // a24 = (A + 2) / 4
+        let a24 = (a.clone() + field.one() * BigInt::from(2)) * (field.one() * BigInt::from(4)).inv();
+
+        // (x0 : z0) = [k]P and (x1 : z1) = [k + 1]P
+        let (mut x0, mut z0) = (field.one(), field.zero());
+        let (mut x1, mut z1) = (x_p.clone(), field.one());
+        for i in (0..bits).rev() {
+            let limb = scalar.get(i / 64).copied().unwrap_or(0);
+            let bit = Choice::from(((limb >> (i % 64)) & 1) as u8);
+            F::Element::conditional_swap(&mut x0, &mut x1, bit);
+            F::Element::conditional_swap(&mut z0, &mut z1, bit);
+
+            // xDBLADD with the difference P
+            let (t0, t1) = (x0.clone() + z0.clone(), x0.clone() - z0.clone());
+            let (t2, t3) = (x1.clone() + z1.clone(), x1.clone() - z1.clone());
+            let (t0_sqr, t1_sqr) = (t0.clone() * t0.clone(), t1.clone() * t1.clone());
+            let (u, v) = (t1 * t2, t0 * t3);
+            let e = t0_sqr.clone() - t1_sqr.clone();
+            x0 = t0_sqr * t1_sqr.clone();
+            z0 = e.clone() * (t1_sqr + a24.clone() * e);
+            x1 = (u.clone() + v.clone()) * (u.clone() + v.clone());
+            z1 = x_p.clone() * (u.clone() - v.clone()) * (u - v);
+
+            F::Element::conditional_swap(&mut x0, &mut x1, bit);
+            F::Element::conditional_swap(&mut z0, &mut z1, bit);
         }
 
-        let lambda = match self.clone() == rhs.clone() {
-            false => {(self.y.clone() - rhs.y.clone()) * (self.x.clone() - rhs.x.clone()).inv()},
-            true => {(self.x.clone() * self.x.clone() * BigInt::from(3) + self.curve.A.clone() * self.x.clone() * BigInt::from(2) + self.curve.field.one()) * (self.y.clone() * BigInt::from(2)).inv()},
+        // Okeya-Sakurai y-coordinate recovery of [k]P from P, x([k]P) and x([k + 1]P)
+        let v1 = x_p.clone() * z0.clone();
+        let v3 = (x0.clone() - v1.clone()) * (x0.clone() - v1.clone()) * x1;
+        let v1_a = a.clone() * z0.clone() * BigInt::from(2);
+        let v2 = (x0.clone() + v1 + v1_a.clone()) * (x_p.clone() * x0.clone() + z0.clone());
+        let v2 = (v2 - v1_a * z0.clone()) * z1.clone();
+        let y = v2 - v3;
+        let v1 = self.curve.coefficients.B.clone() * y_p.clone() * z0.clone() * z1.clone() * BigInt::from(2);
+        let mut result = [v1.clone() * x0, y, v1 * z0.clone()];
+
+        // the formulas give (0 : 0 : 0) when [k]P or [k + 1]P is the point at infinity, i.e. k = 0 or -1 modulo the order
+        let select = |result : &mut [F::Element; 3], point : [F::Element; 3], choice : Choice| {
+            for (r, p) in result.iter_mut().zip(point) {
+                *r = F::Element::conditional_select(r, &p, choice);
+            }
         };
+        select(&mut result, [x_p.clone(), -y_p.clone(), one.clone()], z1.ct_eq(&zero));
+        select(&mut result, [zero.clone(), one.clone(), zero.clone()], z0.ct_eq(&zero));
 
-        let new_x = lambda.clone() * lambda.clone() - (self.x.clone() + rhs.x.clone()) - self.curve.A.clone();
-        let new_y = lambda.clone() * (self.x.clone() - new_x.clone()) - self.y.clone();
+        // the ladder can't use the difference of order 2, but then [k]P is P for odd k and O for even k
+        let odd = Choice::from((bits > 0) as u8 & (scalar.first().copied().unwrap_or(0) & 1) as u8);
+        let mut multiple = [zero.clone(), one.clone(), zero.clone()];
+        select(&mut multiple, [x_p, y_p.clone(), one], odd);
+        select(&mut result, multiple, y_p.ct_eq(&zero));
 
-        assert!(new_y.clone() * new_y.clone() == new_x.clone() * new_x.clone() * new_x.clone() + self.curve.A.clone() * new_x.clone() * new_x.clone() + new_x.clone(),
-        "Addition is wrong!!!");
-        self.curve.gen((&new_x, &new_y, &self.curve.field.one()))
+        let [x, y, z] = result;
+        self.with_coordinates((&x, &y, &z))
     }
 }
 
+/// The reduced coordinates (x : y : 1) or (0 : 1 : 0) as in Sage
+impl<F> fmt::Display for MontgomeryCurvePoint<F> where F: Field, F::Element: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut point = self.clone();
+        point.reduce();
+        write!(f, "({} : {} : {})", point.x, point.y, point.z)
+    }
+}
+
+impl<F> GroupElement for MontgomeryCurvePoint<F> where F: Field {
+    fn is_zero(&self) -> bool {
+        self.z.is_zero()
+    }
+}
+
+impl<F> PartialEq for MontgomeryCurvePoint<F> where F: Field {
+    fn eq(&self, rhs : &Self) -> bool {
+        super::projective_eq(&[&self.x, &self.y, &self.z], &[&rhs.x, &rhs.y, &rhs.z])
+    }
+}
+
+/// (x : -y : z), which is the point at infinity (0 : -1 : 0) for itself
+impl<F> Neg for MontgomeryCurvePoint<F> where F: Field {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.with_coordinates((&self.x, &-self.y.clone(), &self.z))
+    }
+}
+
+/// Projective addition without inversion through the twisted Edwards curve B(A + 2) x^2 + y^2 = 1 + B(A - 2) x^2 y^2,
+/// which is isomorphic by (u : v : w) -> ((u : Bv), (u - w : u + w)) with the point at infinity and (0, 0)
+/// going to ((0 : 1), (1 : 1)) and ((0 : 1), (-1 : 1))
+/// The addition laws of the completed Edwards curve are defined for all the pairs of points, see [add_completed](super::edwards::add_completed)
 #[impl_bin_ops]
-impl<'a, F> Sub for MontgomeryCurvePoint<'a, F> where F: Field<'a> + 'a {
-    fn sub(self, rhs : MontgomeryCurvePoint<'a, F>) -> MontgomeryCurvePoint<'a, F> {
+impl<F> Add for MontgomeryCurvePoint<F> where F: Field {
+    fn add(self, rhs : MontgomeryCurvePoint<F>) -> MontgomeryCurvePoint<F> {
+        let field = &self.curve.field;
+        let (zero, one) = (field.zero(), field.one());
+        let (a, b) = (&self.curve.coefficients.A, &self.curve.coefficients.B);
+        let two = one.clone() + one.clone();
+        let to_edwards = |(u, v, w) : (&F::Element, &F::Element, &F::Element)| {
+            match (u.is_zero(), w.is_zero()) {
+                (true, true) => ((zero.clone(), one.clone()), (one.clone(), one.clone())),
+                (true, false) => ((zero.clone(), one.clone()), (-one.clone(), one.clone())),
+                _ => ((u.clone(), b.clone() * v.clone()), (u.clone() - w.clone(), u.clone() + w.clone())),
+            }
+        };
+
+        let ((x1, z1), (y1, t1)) = to_edwards(self.coordinates());
+        let ((x2, z2), (y2, t2)) = to_edwards(rhs.coordinates());
+        let (a_edwards, d_edwards) = (b.clone() * (a.clone() + two.clone()), b.clone() * (a.clone() - two));
+        let ((x, z), (y, t)) = add_completed(&a_edwards, &d_edwards, ((&x1, &z1), (&y1, &t1)), ((&x2, &z2), (&y2, &t2)));
+
+        // ((x : z), (y : t)) -> ((t + y)Bx : (t + y)z : (t - y)Bx), where x = 0 only at the images of the point at infinity and (0, 0)
+        if x.is_zero() {
+            return match y == t {
+                true => self.identity(),
+                false => self.with_coordinates((&zero, &zero, &one)),
+            };
+        }
+        let t_plus_y = t.clone() + y.clone();
+        let b_x = b.clone() * x;
+        self.with_coordinates((&(t_plus_y.clone() * b_x.clone()), &(t_plus_y * z), &((t - y) * b_x)))
+    }
+}
+
+#[impl_bin_ops]
+impl<F> Sub for MontgomeryCurvePoint<F> where F: Field {
+    fn sub(self, rhs : MontgomeryCurvePoint<F>) -> MontgomeryCurvePoint<F> {
         self + (-rhs.clone())
     }
 }
 
 /// Scalar multiplication
-impl<'a, F> Mul<BigInt> for MontgomeryCurvePoint<'a, F> where F: Field<'a> + 'a {
+impl<F> Mul<BigInt> for MontgomeryCurvePoint<F> where F: Field {
     type Output = Self;
-    fn mul(self, rhs : BigInt) -> MontgomeryCurvePoint<'a, F> {
-        let mut result = self.curve.zero();
-        let mut tmp_value = self.clone();
-        for digit in rhs.iter_u32_digits() {
-            for i in 0..32 {
-                if ((digit >> i) & 1) == 1 {
-                    result = result.clone() + tmp_value.clone();
+    fn mul(self, rhs : BigInt) -> MontgomeryCurvePoint<F> {
+        let identity = self.identity();
+        super::scalar_mul(self, identity, &rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::field::{fp::{FiniteField, FiniteFieldElement}, fp_mont::MontgomeryField, Field, FieldElement};
+    use crate::field::counting::{self, CountingField};
+    use num::{BigUint, BigInt};
+    use crate::field::hash_to_field::{hash_to_field, sgn0};
+    use crate::field::fp2::FiniteField2;
+    use crate::sage::ParseError;
+    use crate::field::params::Fp434Sqr;
+    use num::Num;
+    use rand::{SeedableRng, rngs::StdRng};
+    use sha2::{Sha256, Sha512};
+    use crate::group::{Group, GroupElement};
+
+    use super::{MontgomeryCurve, MontgomeryCurvePoint};
+    use std::sync::Arc;
+    use crate::elliptic_curve::{PointError, affine_points};
+
+    /// Curves and points own handles of their field, so they can be returned from functions
+    fn random_curve_point(prime : u32) -> (MontgomeryCurve<FiniteField>, MontgomeryCurvePoint<FiniteField>) {
+        let fp = FiniteField::new(&BigUint::from(prime));
+        let curve = MontgomeryCurve::new(&fp, fp.zero());
+        let point = curve.rand();
+        (curve, point)
+    }
+
+    #[test]
+    fn batch_reduce_test() {
+        let fp = FiniteField::new(&BigUint::from(1000003_u32));
+        let curve = MontgomeryCurve::new(&fp, fp.gen(&BigInt::from(6)));
+
+        let mut points : Vec<MontgomeryCurvePoint<FiniteField>> = vec![curve.zero()];
+        points.extend((0..20).map(|_| curve.rand()));
+        let mut scaled = points.clone();
+        for point in scaled.iter_mut() {
+            let mut lambda = fp.rand(None);
+            while lambda.is_zero() {lambda = fp.rand(None);}
+            point.scale(&lambda);
+        }
+
+        MontgomeryCurvePoint::batch_reduce(&mut scaled);
+        for (point, reduced) in points.iter().zip(scaled.iter()) {
+            assert_eq!((&point.x, &point.y, &point.z), (&reduced.x, &reduced.y, &reduced.z));
+        }
+    }
+
+    #[test]
+    fn mul_ct_test() {
+        let fp = CountingField { field : MontgomeryField::<1>::new(&BigUint::from(1000003_u32)) };
+        let curve = MontgomeryCurve::new(&fp, fp.zero());
+        let point = curve.rand();
+
+        let mut counts = vec![];
+        for scalar in [0_u64, 1, 2, 12345, 999999, (1 << 20) - 1] {
+            counting::reset();
+            let result = point.mul_ct(&[scalar], 20);
+            counts.push(counting::count());
+            assert_eq!(result, point.clone() * BigInt::from(scalar));
+        }
+
+        assert!(counts.iter().all(|count| *count == counts[0]), "The operations depend on the scalar : {counts:?}");
+
+        // the recovery divides by B
+        let twist = MontgomeryCurve::new_with_b(&fp, fp.zero(), -fp.one() * BigInt::from(2));
+        let twist_point = twist.rand();
+        for scalar in [1_u64, 2, 12345, 999999] {
+            assert_eq!(twist_point.mul_ct(&[scalar], 20), twist_point.clone() * BigInt::from(scalar));
+        }
+
+        // the degenerate cases : k = -1 modulo the order since (p + 1)P = O, and the point (0, 0) of order 2
+        let p = 1000003_u64;
+        let t = curve.gen((&fp.zero(), &fp.zero(), &fp.one()));
+        let order4 = curve.gen((&-fp.one(), &(-fp.one() * BigInt::from(2)).sqrt().unwrap(), &fp.one()));
+        for (point, scalar) in [(point.clone(), p), (point.clone(), p + 1), (order4.clone(), 3), (order4, 7),
+            (t.clone(), 1), (t.clone(), 2), (t.clone(), 12345), (t, 0)] {
+            counting::reset();
+            let result = point.mul_ct(&[scalar], 20);
+            assert_eq!(counting::count(), counts[0]);
+            assert_eq!(result, point.clone() * BigInt::from(scalar));
+            assert!(result.is_on_curve());
+        }
+    }
+
+    #[test]
+    fn seeded_rng_test() {
+        let fp = FiniteField::new(&BigUint::from(1000033_u32));
+        let curve = MontgomeryCurve::new(&fp, fp.gen(&BigInt::from(6)));
+        let (mut rng1, mut rng2) = (StdRng::seed_from_u64(7), StdRng::seed_from_u64(7));
+
+        for _ in 0..10 {
+            assert_eq!(curve.rand_with_rng(&mut rng1), curve.rand_with_rng(&mut rng2));
+        }
+        assert_eq!(curve.is_supersingular_with_rng(&mut rng1), curve.is_supersingular_with_rng(&mut rng2));
+
+        // y^2 = x^3 + x is supersingular for p = 3 mod 4, so all the 10 test points are drawn
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let curve = MontgomeryCurve::new(&fp, fp.zero());
+        assert!(curve.is_supersingular_with_rng(&mut rng1));
+        for _ in 0..10 {
+            curve.rand_with_rng(&mut rng2);
+        }
+        assert_eq!(curve.rand_with_rng(&mut rng1), curve.rand_with_rng(&mut rng2));
+    }
+
+    #[test]
+    fn owned_curve_test() {
+        let curves : Vec<_> = [103_u32, 107, 127]
+            .iter().map(|prime| (*prime, random_curve_point(*prime))).collect();
+
+        for (prime, (curve, point)) in curves {
+            // the points of the curve and the results of the group operations share its coefficients
+            let sum = point.clone() + point.clone() * BigInt::from(3);
+            for shared in [&sum, &point, &curve.zero(), &curve.rand(), &curve.gen(point.coordinates())] {
+                assert!(Arc::ptr_eq(&shared.curve.coefficients, &curve.coefficients));
+            }
+            assert!((point * BigInt::from(prime + 1)).is_zero());
+            assert!(curve.is_supersingular(), "The curve is ordinary");
+        }
+    }
+
+
+    #[test]
+    fn hash_to_curve_test() {
+        // RFC 9380, appendix J.7.1 : curve25519_XMD:SHA-512_ELL2_RO_
+        let prime = (BigUint::from(1_u32) << 255) - BigUint::from(19_u32);
+        let fp = FiniteField::new(&prime);
+        let curve = MontgomeryCurve::new(&fp, fp.gen(&BigInt::from(486662)));
+        let dst = b"QUUX-V01-CS02-with-curve25519_XMD:SHA-512_ELL2_RO_";
+        let point = |x : &str, y : &str| curve.gen((
+            &fp.gen(&BigInt::from_str_radix(x, 16).unwrap()),
+            &fp.gen(&BigInt::from_str_radix(y, 16).unwrap()),
+            &fp.one(),
+        ));
+
+        let vectors : [(&[u8], [&str; 4]); 2] = [
+            (b"", ["36b4df0c864c64707cbf6cf36e9ee2c09a6cb93b28313c169be29561bb904f98",
+                   "6cd59d664fb58c66c892883cd0eb792e52055284dac3907dd756b45d15c3983d",
+                   "2de3780abb67e861289f5749d16d3e217ffa722192d16bbd9d1bfb9d112b98c0",
+                   "3b5dc2a498941a1033d176567d457845637554a2fe7a3507d21abd1c1bd6e878"]),
+            (b"abc", ["16b3d86e056b7970fa00165f6f48d90b619ad618791661b7b5e1ec78be10eac1",
+                      "4ab256422d84c5120b278cbdfc4e1facc5baadffeccecf8ee9bf3946106d50ca",
+                      "2b4419f1f2d48f5872de692b0aca72cc7b0a60915dd70bde432e826b6abc526d",
+                      "1b8235f255a268f0a6fa8763e97eb3d22d149343d495da1160eff9703f2d07dd"]),
+        ];
+        for (msg, [q0_x, q0_y, p_x, p_y]) in vectors {
+            let u = hash_to_field::<_, Sha512>(&fp, msg, dst, 2);
+            assert_eq!(curve.map_to_curve(&u[0]), point(q0_x, q0_y));
+            assert_eq!(curve.hash_to_curve::<Sha512>(msg, dst) * BigInt::from(8), point(p_x, p_y));
+        }
+    }
+
+    #[test]
+    fn field2_hash_to_curve_test() {
+        let fp2 = Fp434Sqr::new();
+        let curve = MontgomeryCurve::new(&fp2, fp2.gen((&BigInt::from(6), &BigInt::from(0))));
+        let point = curve.hash_to_curve::<Sha256>(b"abc", b"DST-A");
+        assert_eq!(point, curve.hash_to_curve::<Sha256>(b"abc", b"DST-A"));
+        assert_ne!(point, curve.hash_to_curve::<Sha256>(b"abc", b"DST-B"));
+        assert_ne!(point, curve.encode_to_curve::<Sha256>(b"abc", b"DST-A"));
+        assert_eq!(curve.point_from_bytes(&point.to_bytes()), Ok(point));
+
+        // the images of u and -u share x and the points are on the curve
+        for u in [fp2.zero(), fp2.one(), fp2.rand(None)] {
+            let point = curve.map_to_curve(&u);
+            assert_eq!(point, curve.map_to_curve(&-u));
+            assert_eq!(curve.point_from_bytes(&point.to_bytes()), Ok(point));
+        }
+    }
+
+    #[test]
+    fn svdw_test() {
+        // y^2 = x^3 + x goes through Shallue-van de Woestijne, including the u with 1 - c1 * u^2 = 0 or 1 + c1 * u^2 = 0
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let curve = MontgomeryCurve::new(&fp, fp.zero());
+        let c1 = curve.svdw_rhs(&curve.svdw_z());
+        let mut images = vec![];
+        for n in 0..431 {
+            let u = fp.gen(&BigInt::from(n));
+            let point = curve.map_to_curve(&u);
+            assert!(point.is_on_curve());
+            let y = point.coordinates().1;
+            assert!(y.is_zero() || sgn0(&fp, y) == sgn0(&fp, &u));
+            if ((fp.one() - c1.clone() * u.clone() * u.clone()) * (fp.one() + c1.clone() * u.clone() * u.clone())).is_zero() {
+                // x1 = x2 = -Z / 2 and x3 = Z
+                let z = curve.svdw_z();
+                assert!([-z.clone() * fp.gen(&BigInt::from(2)).inv(), z].contains(point.coordinates().0));
+            }
+            images.push(point);
+        }
+        // it isn't constant like Elligator 2 would be for A = 0
+        assert!(images.iter().filter(|point| **point != images[0]).count() > 100);
+
+        let fp2 = Fp434Sqr::new();
+        let curve = MontgomeryCurve::new(&fp2, fp2.zero());
+        let point = curve.hash_to_curve::<Sha256>(b"abc", b"DST-A");
+        assert!(point.is_on_curve());
+        assert_eq!(point, curve.hash_to_curve::<Sha256>(b"abc", b"DST-A"));
+        assert_ne!(point, curve.hash_to_curve::<Sha256>(b"abc", b"DST-B"));
+
+        // both methods land on the twists B * y^2 = x^3 + A * x^2 + x
+        for a in [0, 6] {
+            let twist = MontgomeryCurve::new_with_b(&fp, fp.gen(&BigInt::from(a)), fp.gen(&BigInt::from(5)));
+            for n in 0..431 {
+                assert!(twist.map_to_curve(&fp.gen(&BigInt::from(n))).is_on_curve());
+            }
+            assert!(twist.hash_to_curve::<Sha256>(b"abc", b"DST-A").is_on_curve());
+        }
+    }
+
+    #[test]
+    fn display_test() {
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(430_u32));
+        let curve = MontgomeryCurve::new(&fp2, fp2.gen((&BigInt::from(3), &BigInt::from(6))));
+        let expected = "Elliptic Curve defined by y^2 = x^3 + (6*i + 3)*x^2 + x over Finite Field in i of size 431^2";
+        assert_eq!(curve.to_string(), expected);
+        assert_eq!(MontgomeryCurve::from_str(&fp2, expected).map(|curve| curve.coefficients.A.clone()), Ok(curve.coefficients.A.clone()));
+        let curve0 = MontgomeryCurve::new(&fp2, fp2.zero());
+        assert_eq!(curve0.to_string(), "Elliptic Curve defined by y^2 = x^3 + x over Finite Field in i of size 431^2");
+        assert_eq!(MontgomeryCurve::from_str(&fp2, "Elliptic Curve defined by y^2 = x^3 + 2*x^2 + x").map(|curve| curve.coefficients.A.clone()),
+            Err(ParseError::SingularCurve));
+        assert_eq!(MontgomeryCurve::from_str(&fp2, "Elliptic Curve defined by y^2 = x^3 + 1").map(|curve| curve.coefficients.A.clone()),
+            Err(ParseError::InvalidSyntax));
+        assert_eq!(MontgomeryCurve::from_str(&fp2, "Elliptic Curve defined by y = x^3 + x").map(|curve| curve.coefficients.A.clone()),
+            Err(ParseError::InvalidSyntax));
+
+        let twist = MontgomeryCurve::new_with_b(&fp2, curve.coefficients.A.clone(), fp2.gen((&BigInt::from(1), &BigInt::from(2))));
+        let expected = "Elliptic Curve defined by (2*i + 1)*y^2 = x^3 + (6*i + 3)*x^2 + x over Finite Field in i of size 431^2";
+        assert_eq!(twist.to_string(), expected);
+        assert_eq!(MontgomeryCurve::from_str(&fp2, expected).map(|curve| (curve.coefficients.A.clone(), curve.coefficients.B.clone())), Ok((twist.coefficients.A.clone(), twist.coefficients.B.clone())));
+        let point = twist.rand();
+        assert_eq!(twist.point_from_str(&point.to_string()), Ok(point.clone()));
+        assert_eq!(curve.point_from_str(&point.to_string()), Err(ParseError::NotOnCurve));
+
+        assert_eq!(curve.zero().to_string(), "(0 : 1 : 0)");
+        assert_eq!(curve.point_from_str("(0 : 1 : 0)"), Ok(curve.zero()));
+        for _ in 0..20 {
+            let mut point = curve.rand();
+            assert_eq!(curve.point_from_str(&point.to_string()), Ok(point.clone()));
+            point.scale(&fp2.gen((&BigInt::from(2), &BigInt::from(5))));
+            assert!(point.to_string().ends_with(" : 1)"));
+            let projective = format!("({} : {} : {})", point.x, point.y, point.z);
+            assert_eq!(curve.point_from_str(&projective), Ok(point));
+        }
+        assert_eq!(curve.point_from_str("(1 : 1 : 1)"), Err(ParseError::NotOnCurve));
+        assert_eq!(curve.point_from_str("(1 : 1)"), Err(ParseError::InvalidSyntax));
+    }
+
+    #[test]
+    fn division_polynomial_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        let curve = MontgomeryCurve::new(&fp, fp.gen(&BigInt::from(5)));
+        let rhs = |x : &FiniteFieldElement| (x.clone() * x.clone() + curve.coefficients.A.clone() * x.clone() + fp.one()) * x.clone() * BigInt::from(4);
+        let f = curve.division_polynomials(8);
+        assert_eq!(f[5], curve.division_polynomial(5));
+
+        // x([n]P) = x - ψ_{n-1} ψ_{n+1} / ψ_n^2
+        for _ in 0..10 {
+            let mut point = curve.rand();
+            point.reduce();
+            let x = point.x.clone();
+            for n in 2..8 {
+                let (psi_sqr, numerator) = match n % 2 {
+                    0 => (f[n].eval(&x) * f[n].eval(&x) * rhs(&x), f[n - 1].eval(&x) * f[n + 1].eval(&x)),
+                    _ => (f[n].eval(&x) * f[n].eval(&x), f[n - 1].eval(&x) * f[n + 1].eval(&x) * rhs(&x)),
+                };
+                let mut multiple = point.clone() * BigInt::from(n);
+                match multiple.is_zero() {
+                    true => assert!(psi_sqr.is_zero()),
+                    false => {
+                        multiple.reduce();
+                        assert_eq!(multiple.x * psi_sqr.clone(), x.clone() * psi_sqr - numerator);
+                    },
                 }
-                tmp_value = tmp_value.clone() + tmp_value.clone();
             }
         }
 
-        match rhs.sign() {
-            Sign::Minus => -result,
-            _ => result,
+        // compare the n-torsion points with the brute force
+        let mut points = vec![curve.zero()];
+        for x in (0..103).map(|x| fp.gen(&BigInt::from(x))) {
+            if let Some(y) = (rhs(&x) * fp.gen(&BigInt::from(4)).inv()).sqrt() {
+                points.push(curve.gen((&x, &y, &fp.one())));
+                if !y.is_zero() {
+                    points.push(curve.gen((&x, &-y, &fp.one())));
+                }
+            }
+        }
+        for n in 1..=8 {
+            let torsion = curve.torsion_points(n);
+            let expected = points.iter().filter(|&point| (point.clone() * BigInt::from(n)).is_zero()).count();
+            assert_eq!(torsion.len(), expected);
+            assert!(torsion.iter().all(|point| points.contains(point) && (point.clone() * BigInt::from(n)).is_zero()));
+            assert_eq!(curve.torsion_polynomial(n).degree(), match n % 2 {
+                0 => (n * n + 2) / 2,
+                _ => (n * n - 1) / 2,
+            });
         }
     }
-}
 
-#[cfg(test)]
-mod tests{
-    use crate::field::{fp::FiniteField, Field};
-    use num::{BigUint, BigInt};
-    use crate::group::GroupElement;
+    #[test]
+    fn torsion_field_degree_test() {
+        // y^2 = x^3 + x is supersingular over F_431, the Frobenius π satisfies π^2 = -431
+        // so E[n] is defined over F_{431^k} for the order k of π on E[n]
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let curve = MontgomeryCurve::new(&fp, fp.zero());
+        for (n, k) in [(2, 2), (3, 2), (4, 2), (5, 4), (7, 12)] {
+            assert_eq!(curve.torsion_field_degree(n), k);
+        }
 
-    use super::{MontgomeryCurve};
+        // E(F_431) is cyclic of order 432 so its 3-torsion has 3 points
+        assert_eq!(curve.torsion_points(3).len(), 3);
+        assert_eq!(curve.torsion_points(2).len(), 2);
+
+        // -y^2 = x^3 + x is isomorphic by x -> -x, the torsion points of the twists are on them
+        let twist = MontgomeryCurve::new_with_b(&fp, fp.zero(), -fp.one());
+        assert_eq!(twist.torsion_points(3).len(), 3);
+        assert_eq!(twist.torsion_field_degree(5), 4);
+        let twist = MontgomeryCurve::new_with_b(&fp, fp.gen(&BigInt::from(6)), fp.gen(&BigInt::from(7)));
+        for n in [2, 3, 4] {
+            assert!(twist.torsion_points(n).iter().all(|point| point.is_on_curve() && (point.clone() * BigInt::from(n)).is_zero()));
+        }
+    }
+
+    /// All the points of B * y^2 = x^3 + A * x^2 + x over a small prime field
+    fn all_points(curve : &MontgomeryCurve<FiniteField>) -> Vec<MontgomeryCurvePoint<FiniteField>> {
+        let one = curve.field.one();
+        let mut points = vec![curve.zero()];
+        points.extend(affine_points(&curve.field, |x, y| curve.point((x, y, &one)).ok()));
+        points
+    }
+
+    #[test]
+    fn group_law_test() {
+        // every nonsingular curve and its quadratic twist over small primes, all pairs and triples of points
+        for prime in [3_u32, 5, 7, 11, 13] {
+            let fp = FiniteField::new(&BigUint::from(prime));
+            let non_square = (2..prime).map(|b| fp.gen(&BigInt::from(b))).find(|b| !b.is_square()).unwrap();
+            for (a, b) in (0..prime).filter(|a| (a * a) % prime != 4 % prime).flat_map(|a| [(a, fp.one()), (a, non_square.clone())]) {
+                let curve = MontgomeryCurve::new_with_b(&fp, fp.gen(&BigInt::from(a)), b);
+                let points = all_points(&curve);
+                let twist = MontgomeryCurve::new_with_b(&fp, curve.coefficients.A.clone(), curve.coefficients.B.clone() * non_square.clone());
+                assert_eq!(points.len() + all_points(&twist).len(), 2 * prime as usize + 2);
+                let order = BigInt::from(points.len());
+                for p in &points {
+                    assert!((p + curve.zero()) == *p && (curve.zero() + p) == *p);
+                    assert!((p + (-p.clone())).is_zero());
+                    assert!((p.clone() * order.clone()).is_zero());
+                    for q in &points {
+                        let sum = p + q;
+                        assert!(sum.is_on_curve() && points.contains(&sum));
+                        assert_eq!(sum, q + p);
+                        assert_eq!(sum.clone() - q, *p);
+                        for r in &points {
+                            assert_eq!((p + q) + r, p + (q + r));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn point_validation_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        let curve = MontgomeryCurve::new(&fp, fp.gen(&BigInt::from(5)));
+        let c = |n : i64| fp.gen(&BigInt::from(n));
+
+        // the same points in other projective representations
+        let t = curve.point((&c(0), &c(0), &c(1))).unwrap();
+        assert_eq!(curve.point((&c(0), &c(0), &c(7))), Ok(t.clone()));
+        assert_eq!(curve.point((&c(0), &c(5), &c(0))), Ok(curve.zero()));
+        assert_ne!(t, curve.zero());
+        assert!((t.clone() + t.clone()).is_zero());
+        assert_eq!(-t.clone(), t);
+        assert_eq!(-curve.zero(), curve.zero());
+
+        // a point of order other than 2, so that it differs from its negation
+        let mut p = curve.rand();
+        while p.y.is_zero() {
+            p = curve.rand();
+        }
+        let q = -p.clone();
+        assert!(p.is_on_curve() && q.is_on_curve());
+        assert_ne!(p, q);
+        p.scale(&c(9));
+        assert_eq!(-q.clone(), p);
+        assert_eq!(p.clone() + p.clone() + t.clone(), (p.clone() + t.clone()) + p.clone());
+        assert!((p + q).is_zero());
+
+        assert_eq!(curve.point((&c(0), &c(0), &c(0))), Err(PointError::InvalidCoordinates));
+        assert_eq!(curve.point((&c(1), &c(1), &c(1))), Err(PointError::NotOnCurve));
+        assert!(!curve.gen((&c(1), &c(1), &c(1))).is_on_curve());
+    }
 
     #[test]
     fn montgomery_curve_test() {
@@ -0,0 +1,160 @@
+//! Montgomery curves with projective coefficients
+//!
+//! The curve B y^2 = x^3 + (A / C) x^2 + x is kept as (A : C) so that the codomains of isogenies,
+//! which come out as (A + 2C : 4C) or (A + 2C : A - 2C), need no inversion.
+//! The affine coefficient A / C is only computed by [normalize](ProjectiveMontgomeryCurve::normalize).
+//! The x-only arithmetic doesn't depend on B, so the curves built from its constants have B = 1.
+use crate::field::{Field, FieldElement};
+use super::mont_curve::MontgomeryCurve;
+use super::kummer::KummerLine;
+use num::BigInt;
+
+/// The montgomery curve with the projective coefficient (A : C) and the coefficient B
+#[derive(Clone, Debug)]
+pub struct ProjectiveMontgomeryCurve<F> where F: Field {
+    field : F,
+    a : F::Element,
+    c : F::Element,
+    b : F::Element,
+}
+
+impl<F> ProjectiveMontgomeryCurve<F> where F: Field {
+    /// The curve (A : C) with B = 1, C must be nonzero
+    pub fn new(field : &F, a : F::Element, c : F::Element) -> Self {
+        Self::new_with_b(field, a, c, field.one())
+    }
+
+    /// The curve (A : C) with the coefficient B, a quadratic twist of the one with B = 1 when B isn't a square
+    pub fn new_with_b(field : &F, a : F::Element, c : F::Element, b : F::Element) -> Self {
+        assert!(!c.is_zero(), "The coefficient C must be nonzero");
+        assert!(!b.is_zero(), "The coefficient B must be nonzero");
+        ProjectiveMontgomeryCurve {
+            field : field.clone(),
+            a,
+            c,
+            b,
+        }
+    }
+
+    /// The curve from (A + 2C : 4C), which is (4(A + 2C) - 2 * 4C : 4C)
+    pub fn from_a24_plus(field : &F, a24_plus : &F::Element, c24 : &F::Element) -> Self {
+        let a = (a24_plus.clone() * BigInt::from(2) - c24.clone()) * BigInt::from(2);
+        Self::new(field, a, c24.clone())
+    }
+
+    /// The curve from (A + 2C : A - 2C), which is (2((A + 2C) + (A - 2C)) : (A + 2C) - (A - 2C))
+    pub fn from_a24_plus_minus(field : &F, a24_plus : &F::Element, a24_minus : &F::Element) -> Self {
+        let a = (a24_plus.clone() + a24_minus.clone()) * BigInt::from(2);
+        Self::new(field, a, a24_plus.clone() - a24_minus.clone())
+    }
+
+    /// The coefficients (A, C)
+    pub fn coefficients(&self) -> (&F::Element, &F::Element) {
+        (&self.a, &self.c)
+    }
+
+    /// The coefficient B
+    pub fn coefficient_b(&self) -> &F::Element {
+        &self.b
+    }
+
+    /// The constants (A + 2C, 4C) of the doubling formulas
+    pub fn a24_plus(&self) -> (F::Element, F::Element) {
+        let c2 = self.c.clone() * BigInt::from(2);
+        (self.a.clone() + c2.clone(), c2 * BigInt::from(2))
+    }
+
+    /// The constants (A + 2C, A - 2C) of the tripling formulas
+    pub fn a24_plus_minus(&self) -> (F::Element, F::Element) {
+        let c2 = self.c.clone() * BigInt::from(2);
+        (self.a.clone() + c2.clone(), self.a.clone() - c2)
+    }
+
+    /// The Kummer line with the constants (A + 2C : 4C)
+    pub fn kummer_line(&self) -> KummerLine<F> {
+        let (a24_plus, c24) = self.a24_plus();
+        KummerLine::new(&self.field, a24_plus, c24)
+    }
+
+    /// The j-invariant as (256 (A^2 - 3C^2)^3 : C^4 (A^2 - 4C^2))
+    pub fn j_invariant_projective(&self) -> (F::Element, F::Element) {
+        let a_sqr = self.a.clone() * self.a.clone();
+        let c_sqr = self.c.clone() * self.c.clone();
+        let t = a_sqr.clone() - c_sqr.clone() * BigInt::from(3);
+        let numerator = t.clone() * t.clone() * t * BigInt::from(256);
+        let denominator = c_sqr.clone() * c_sqr.clone() * (a_sqr - c_sqr * BigInt::from(4));
+        (numerator, denominator)
+    }
+
+    /// The j-invariant with one inversion
+    pub fn j_invariant(&self) -> F::Element {
+        let (numerator, denominator) = self.j_invariant_projective();
+        numerator * denominator.inv()
+    }
+
+    /// The curve with the affine coefficient A / C and the same B, it costs an inversion
+    pub fn normalize(&self) -> MontgomeryCurve<F> {
+        MontgomeryCurve::new_with_b(&self.field, self.a.clone() * self.c.inv(), self.b.clone())
+    }
+}
+
+/// The same curve up to the scaling of (A : C)
+impl<F> PartialEq for ProjectiveMontgomeryCurve<F> where F: Field {
+    fn eq(&self, rhs : &Self) -> bool {
+        self.a.clone() * rhs.c.clone() == rhs.a.clone() * self.c.clone() && self.b == rhs.b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::fp::FiniteField;
+    use crate::field::fp2::FiniteField2;
+    use num::BigUint;
+
+    #[test]
+    fn projective_curve_test() {
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(430_u32));
+        let c = |a : i64, b : i64| fp2.gen((&BigInt::from(a), &BigInt::from(b)));
+
+        let curve = MontgomeryCurve::new(&fp2, c(3, 6));
+        let projective = curve.to_projective();
+        let lambda = c(5, 7);
+        let scaled = ProjectiveMontgomeryCurve::new(&fp2, c(3, 6) * lambda.clone(), lambda.clone());
+        assert_eq!(scaled, projective);
+        assert_ne!(scaled, MontgomeryCurve::new(&fp2, c(3, 5)).to_projective());
+        assert_eq!(scaled.normalize().j_invariant(), curve.j_invariant());
+        assert_eq!(scaled.j_invariant(), curve.j_invariant());
+        let (numerator, denominator) = scaled.j_invariant_projective();
+        assert_eq!(numerator, curve.j_invariant() * denominator);
+
+        // the conversions between the representations
+        let (a24_plus, c24) = scaled.a24_plus();
+        assert_eq!(ProjectiveMontgomeryCurve::from_a24_plus(&fp2, &a24_plus, &c24), projective);
+        let (a24_plus, a24_minus) = scaled.a24_plus_minus();
+        assert_eq!(ProjectiveMontgomeryCurve::from_a24_plus_minus(&fp2, &a24_plus, &a24_minus), projective);
+        assert_eq!(scaled.kummer_line().curve(), projective);
+        assert_eq!(curve.kummer_line().curve().normalize().j_invariant(), curve.j_invariant());
+
+        // the twist by a non-square B keeps it through the projective coefficients
+        let b = (1..).map(|n| c(n, 1)).find(|b| !b.is_square()).unwrap();
+        let twist = MontgomeryCurve::new_with_b(&fp2, c(3, 6), b.clone());
+        let projective_twist = twist.to_projective();
+        assert_eq!(projective_twist.coefficient_b(), &b);
+        assert_ne!(projective_twist, projective);
+        assert_eq!(projective_twist, ProjectiveMontgomeryCurve::new_with_b(&fp2, c(3, 6) * lambda.clone(), lambda, b.clone()));
+        let normalized = projective_twist.normalize();
+        assert_eq!(normalized.coefficients(), twist.coefficients());
+        let point = twist.rand();
+        assert!(normalized.gen(point.coordinates()).is_on_curve());
+        assert_eq!(projective_twist.j_invariant(), curve.j_invariant());
+
+        // the Kummer lines of both agree
+        for _ in 0..10 {
+            let p = curve.rand().to_kummer();
+            let k = BigInt::from(1000);
+            assert_eq!(scaled.kummer_line().ladder(&p, &k), curve.kummer_line().ladder(&p, &k));
+        }
+    }
+}
@@ -0,0 +1,427 @@
+//! The elliptic curves of short Weierstrass form y^2 = x^3 + a * x + b
+//!
+//! The characteristic must not be 2 or 3. A montgomery curve B * y^2 = x^3 + A * x^2 + x is always isomorphic
+//! to a short Weierstrass curve, and a short Weierstrass curve is isomorphic to a montgomery curve
+//! iff x^3 + a * x + b has a root r such that 3r^2 + a is a square, see [montgomery_map](WeierstrassCurve::montgomery_map).
+use crate::field::{Field, FieldElement};
+use crate::group::{Group, GroupElement};
+use crate::sage;
+use crate::poly::Polynomial;
+use super::mont_curve::{MontgomeryCurve, MontgomeryCurvePoint};
+use super::PointError;
+use std::ops::{Neg, Mul};
+use std::fmt;
+use std::sync::Arc;
+use num::BigInt;
+use impl_ops::impl_bin_ops;
+use rand::RngCore;
+
+/// The structure of a short Weierstrass curve
+/// It's a cheap handle to the shared coefficients like [MontgomeryCurve]
+#[derive(Clone, Debug)]
+pub struct WeierstrassCurve<F> where F: Field {
+    field : F,
+    coefficients : Arc<Coefficients<F::Element>>,
+}
+
+/// The coefficients a and b of a short Weierstrass curve
+#[derive(Debug)]
+struct Coefficients<T> {
+    a : T,
+    b : T,
+}
+
+/// The structure of a (projective coordinate)point of a short Weierstrass curve, sharing the curve like [MontgomeryCurvePoint]
+#[derive(Clone, Debug)]
+pub struct WeierstrassCurvePoint<F> where F: Field {
+    curve : WeierstrassCurve<F>,
+    x : F::Element,
+    y : F::Element,
+    z : F::Element,
+}
+
+/// The isomorphism (x, y) -> ((x - r) / s, y / t) from a short Weierstrass curve onto a montgomery curve with B = t^2 / s^3
+#[derive(Clone, Debug)]
+pub struct MontgomeryWeierstrassMap<F> where F: Field {
+    weierstrass : WeierstrassCurve<F>,
+    montgomery : MontgomeryCurve<F>,
+    r : F::Element,
+    s : F::Element,
+    t : F::Element,
+}
+
+impl<F> WeierstrassCurve<F> where F: Field {
+    /// The curve must be nonsingular, i.e. 4a^3 + 27b^2 != 0, over a field of characteristic other than 2 and 3
+    pub fn new(field : &F, a : F::Element, b : F::Element) -> Self {
+        assert!(field.characteristic() > BigInt::from(3), "The characteristic must not be 2 or 3");
+        let curve = WeierstrassCurve {
+            field : field.clone(),
+            coefficients : Arc::new(Coefficients { a, b }),
+        };
+        assert!(!curve.discriminant().is_zero(), "The curve is singular!");
+        curve
+    }
+
+    /// 4a^3 + 27b^2, which is -Δ / 16 for the discriminant Δ
+    fn discriminant(&self) -> F::Element {
+        self.coefficients.a.clone() * self.coefficients.a.clone() * self.coefficients.a.clone() * BigInt::from(4) + self.coefficients.b.clone() * self.coefficients.b.clone() * BigInt::from(27)
+    }
+
+    /// The field of definition
+    pub fn field(&self) -> &F {
+        &self.field
+    }
+
+    /// The coefficients (a, b)
+    pub fn coefficients(&self) -> (&F::Element, &F::Element) {
+        (&self.coefficients.a, &self.coefficients.b)
+    }
+
+    /// The point (x : y : z) without any check, see [point](Self::point) for the validated one
+    pub fn gen(&self, (x, y, z) : (&F::Element, &F::Element, &F::Element)) -> WeierstrassCurvePoint<F> {
+        WeierstrassCurvePoint {
+            curve : self.clone(),
+            x : x.clone(), y : y.clone(), z : z.clone(),
+        }
+    }
+
+    /// The point (x : y : z) if it's a projective point on the curve
+    pub fn point(&self, coordinates : (&F::Element, &F::Element, &F::Element)) -> Result<WeierstrassCurvePoint<F>, PointError> {
+        let point = self.gen(coordinates);
+        if point.x.is_zero() && point.y.is_zero() && point.z.is_zero() {
+            return Err(PointError::InvalidCoordinates);
+        }
+        match point.is_on_curve() {
+            true => Ok(point),
+            false => Err(PointError::NotOnCurve),
+        }
+    }
+
+    /// 1728 * 4a^3 / (4a^3 + 27b^2)
+    pub fn j_invariant(&self) -> F::Element {
+        self.coefficients.a.clone() * self.coefficients.a.clone() * self.coefficients.a.clone() * BigInt::from(4 * 1728) * self.discriminant().inv()
+    }
+
+    /// The right hand side x^3 + a * x + b of the curve equation
+    fn rhs_polynomial(&self) -> Polynomial<F> {
+        Polynomial::new(&self.field, vec![self.coefficients.b.clone(), self.coefficients.a.clone(), self.field.zero(), self.field.one()])
+    }
+
+    /// Generate a nonzero random point using the thread-local random number generator
+    pub fn rand(&self) -> WeierstrassCurvePoint<F> {
+        self.rand_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Generate a nonzero random point using the given random number generator
+    pub fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> WeierstrassCurvePoint<F> {
+        let rhs = self.rhs_polynomial();
+        loop {
+            let x = self.field.rand_with_rng(rng, None);
+            if let Some(y) = rhs.eval(&x).sqrt_with_rng(rng) {
+                return self.gen((&x, &y, &self.field.one()));
+            }
+        }
+    }
+
+    /// The isomorphism onto a montgomery curve using the thread-local random number generator
+    /// None if no root r of x^3 + a * x + b in the field makes 3r^2 + a a square
+    pub fn montgomery_map(&self) -> Option<MontgomeryWeierstrassMap<F>> {
+        self.montgomery_map_with_rng(&mut rand::thread_rng())
+    }
+
+    /// The isomorphism onto a montgomery curve using the given random number generator
+    /// For the root r and s^2 = 3r^2 + a, the point (r, 0) of order 2 goes to (0, 0) on the curve with A = 3r / s and B = 1 / s^3
+    pub fn montgomery_map_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> Option<MontgomeryWeierstrassMap<F>> {
+        self.rhs_polynomial().roots_with_rng(rng).into_iter().find_map(|r| {
+            let s = (r.clone() * r.clone() * BigInt::from(3) + self.coefficients.a.clone()).sqrt_with_rng(rng)?;
+            let s_inv = s.inv();
+            let a = r.clone() * BigInt::from(3) * s_inv.clone();
+            Some(MontgomeryWeierstrassMap {
+                weierstrass : self.clone(),
+                montgomery : MontgomeryCurve::new_with_b(&self.field, a, s_inv.clone() * s_inv.clone() * s_inv),
+                r,
+                s,
+                t : self.field.one(),
+            })
+        })
+    }
+}
+
+impl<F> MontgomeryCurve<F> where F: Field {
+    /// The isomorphism with the short Weierstrass curve, (x, y) -> ((x + A / 3) / B, y / B) with
+    /// a = (3 - A^2) / (3B^2) and b = (2A^3 - 9A) / (27B^3)
+    /// The characteristic must not be 2 or 3
+    pub fn weierstrass_map(&self) -> MontgomeryWeierstrassMap<F> {
+        let field = self.field();
+        assert!(field.characteristic() > BigInt::from(3), "The characteristic must not be 2 or 3");
+        let (a, b) = self.coefficients();
+        let a_sqr = a.clone() * a.clone();
+        let inv3 = (field.one() * BigInt::from(3)).inv();
+        let b_inv = b.inv();
+        let weierstrass_a = (field.one() * BigInt::from(3) - a_sqr.clone()) * inv3.clone() * b_inv.clone() * b_inv.clone();
+        let weierstrass_b = (a_sqr * BigInt::from(2) - field.one() * BigInt::from(9)) * a.clone()
+            * (inv3.clone() * b_inv.clone()).pow(&BigInt::from(3));
+        MontgomeryWeierstrassMap {
+            weierstrass : WeierstrassCurve::new(field, weierstrass_a, weierstrass_b),
+            montgomery : self.clone(),
+            r : a.clone() * inv3 * b_inv.clone(),
+            s : b_inv.clone(),
+            t : b_inv,
+        }
+    }
+}
+
+impl<F> MontgomeryWeierstrassMap<F> where F: Field {
+    pub fn weierstrass_curve(&self) -> &WeierstrassCurve<F> {
+        &self.weierstrass
+    }
+
+    pub fn montgomery_curve(&self) -> &MontgomeryCurve<F> {
+        &self.montgomery
+    }
+
+    /// (x : y : z) -> (t(x - rz) : sy : stz)
+    pub fn to_montgomery(&self, point : &WeierstrassCurvePoint<F>) -> MontgomeryCurvePoint<F> {
+        let x = (point.x.clone() - self.r.clone() * point.z.clone()) * self.t.clone();
+        let z = point.z.clone() * self.s.clone() * self.t.clone();
+        self.montgomery.gen((&x, &(point.y.clone() * self.s.clone()), &z))
+    }
+
+    /// (x : y : z) -> (sx + rz : ty : z)
+    pub fn to_weierstrass(&self, point : &MontgomeryCurvePoint<F>) -> WeierstrassCurvePoint<F> {
+        let (x, y, z) = point.coordinates();
+        let x = self.s.clone() * x.clone() + self.r.clone() * z.clone();
+        self.weierstrass.gen((&x, &(self.t.clone() * y.clone()), z))
+    }
+}
+
+/// The description of Sage, e.g. `Elliptic Curve defined by y^2 = x^3 + 2*x + 3 over Finite Field of size 431`
+impl<F> fmt::Display for WeierstrassCurve<F> where F: Field + fmt::Display, F::Element: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Elliptic Curve defined by y^2 = ")?;
+        sage::write_sum(f, &[
+            (self.field.one().to_string(), "x^3".to_string()),
+            (self.coefficients.a.to_string(), "x".to_string()),
+            (self.coefficients.b.to_string(), String::new()),
+        ])?;
+        write!(f, " over {}", self.field)
+    }
+}
+
+impl<F> Group for WeierstrassCurve<F> where F: Field {
+    type Element = WeierstrassCurvePoint<F>;
+    fn zero(&self) -> Self::Element {
+        self.gen((&self.field.zero(), &self.field.one(), &self.field.zero()))
+    }
+}
+
+impl<F> WeierstrassCurvePoint<F> where F: Field {
+    /// The point (x : y : z) on the same curve sharing its handle
+    fn with_coordinates(&self, (x, y, z) : (&F::Element, &F::Element, &F::Element)) -> Self {
+        WeierstrassCurvePoint {
+            curve : self.curve.clone(),
+            x : x.clone(), y : y.clone(), z : z.clone(),
+        }
+    }
+
+    /// The point at infinity of the same curve
+    fn identity(&self) -> Self {
+        let field = &self.curve.field;
+        self.with_coordinates((&field.zero(), &field.one(), &field.zero()))
+    }
+
+    /// The projective coordinates (x, y, z)
+    pub fn coordinates(&self) -> (&F::Element, &F::Element, &F::Element) {
+        (&self.x, &self.y, &self.z)
+    }
+
+    /// Whether it satisfies y^2 z = x^3 + a x z^2 + b z^3
+    pub fn is_on_curve(&self) -> bool {
+        let (x, y, z) = (&self.x, &self.y, &self.z);
+        let z_sqr = z.clone() * z.clone();
+        let lhs = y.clone() * y.clone() * z.clone();
+        let rhs = x.clone() * x.clone() * x.clone() + (self.curve.coefficients.a.clone() * x.clone() + self.curve.coefficients.b.clone() * z.clone()) * z_sqr;
+        lhs == rhs
+    }
+}
+
+/// The reduced coordinates (x : y : 1) or (0 : 1 : 0) as in Sage
+impl<F> fmt::Display for WeierstrassCurvePoint<F> where F: Field, F::Element: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "(0 : 1 : 0)");
+        }
+        let z_inv = self.z.inv();
+        write!(f, "({} : {} : 1)", self.x.clone() * z_inv.clone(), self.y.clone() * z_inv)
+    }
+}
+
+impl<F> GroupElement for WeierstrassCurvePoint<F> where F: Field {
+    fn is_zero(&self) -> bool {
+        self.z.is_zero()
+    }
+}
+
+impl<F> PartialEq for WeierstrassCurvePoint<F> where F: Field {
+    fn eq(&self, rhs : &Self) -> bool {
+        super::projective_eq(&[&self.x, &self.y, &self.z], &[&rhs.x, &rhs.y, &rhs.z])
+    }
+}
+
+impl<F> Neg for WeierstrassCurvePoint<F> where F: Field {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.with_coordinates((&self.x, &-self.y.clone(), &self.z))
+    }
+}
+
+/// Projective addition without inversion, defined for all the points of the curve
+#[impl_bin_ops]
+impl<F> Add for WeierstrassCurvePoint<F> where F: Field {
+    fn add(self, rhs : WeierstrassCurvePoint<F>) -> WeierstrassCurvePoint<F> {
+        if self.is_zero() {
+            return rhs.clone();
+        }
+
+        if rhs.is_zero() {
+            return self.clone();
+        }
+
+        let (zero, one) = (self.curve.field.zero(), self.curve.field.one());
+        match super::add_projective(&one, &zero, &self.curve.coefficients.a, (&self.x, &self.y, &self.z), (&rhs.x, &rhs.y, &rhs.z)) {
+            Some((x, y, z)) => self.with_coordinates((&x, &y, &z)),
+            None => self.identity(),
+        }
+    }
+}
+
+#[impl_bin_ops]
+impl<F> Sub for WeierstrassCurvePoint<F> where F: Field {
+    fn sub(self, rhs : WeierstrassCurvePoint<F>) -> WeierstrassCurvePoint<F> {
+        self + (-rhs.clone())
+    }
+}
+
+/// Scalar multiplication
+impl<F> Mul<BigInt> for WeierstrassCurvePoint<F> where F: Field {
+    type Output = Self;
+    fn mul(self, rhs : BigInt) -> WeierstrassCurvePoint<F> {
+        let identity = self.identity();
+        super::scalar_mul(self, identity, &rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_curve::affine_points;
+    use crate::field::fp::FiniteField;
+    use crate::field::fp2::FiniteField2;
+    use num::BigUint;
+
+    /// All the points of the curve over a small prime field
+    fn all_points(curve : &WeierstrassCurve<FiniteField>) -> Vec<WeierstrassCurvePoint<FiniteField>> {
+        let one = curve.field().one();
+        let mut points = vec![curve.zero()];
+        points.extend(affine_points(curve.field(), |x, y| curve.point((x, y, &one)).ok()));
+        points
+    }
+
+    #[test]
+    fn weierstrass_group_law_test() {
+        let fp = FiniteField::new(&BigUint::from(13_u32));
+        let c = |n : i64| fp.gen(&BigInt::from(n));
+        for (a, b) in [(1, 1), (2, 0), (0, 5), (3, 7), (12, 0)] {
+            let curve = WeierstrassCurve::new(&fp, c(a), c(b));
+            let points = all_points(&curve);
+            assert!(points.iter().all(|p| Arc::ptr_eq(&p.curve.coefficients, &curve.coefficients)));
+            let order = BigInt::from(points.len());
+            for p in &points {
+                assert!((p + (-p.clone())).is_zero());
+                assert!((p.clone() * order.clone()).is_zero());
+                for q in &points {
+                    assert!(points.contains(&(p + q)));
+                    assert_eq!(p + q, q + p);
+                    for r in &points {
+                        assert_eq!((p + q) + r, p + (q + r));
+                    }
+                }
+            }
+        }
+
+        let curve = WeierstrassCurve::new(&fp, c(2), c(3));
+        assert_eq!(curve.to_string(), "Elliptic Curve defined by y^2 = x^3 + 2*x + 3 over Finite Field of size 13");
+        assert_eq!(curve.zero().to_string(), "(0 : 1 : 0)");
+        assert_eq!(curve.point((&c(0), &c(0), &c(0))), Err(PointError::InvalidCoordinates));
+        assert_eq!(curve.point((&c(0), &c(0), &c(1))), Err(PointError::NotOnCurve));
+        assert_eq!(curve.point((&c(6), &c(12), &c(2))).unwrap().to_string(), "(3 : 6 : 1)");
+    }
+
+    #[test]
+    #[should_panic(expected = "The characteristic must not be 2 or 3")]
+    fn small_characteristic_test() {
+        let fp = FiniteField::new(&BigUint::from(3_u32));
+        WeierstrassCurve::new(&fp, fp.one(), fp.one());
+    }
+
+    #[test]
+    fn montgomery_weierstrass_test() {
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(430_u32));
+        let c = |a : i64, b : i64| fp2.gen((&BigInt::from(a), &BigInt::from(b)));
+
+        // montgomery -> Weierstrass -> montgomery
+        let montgomery = MontgomeryCurve::new(&fp2, c(3, 6));
+        let map = montgomery.weierstrass_map();
+        let weierstrass = map.weierstrass_curve();
+        assert_eq!(weierstrass.j_invariant(), montgomery.j_invariant());
+        let back = weierstrass.montgomery_map().unwrap();
+        assert_eq!(back.weierstrass_curve().coefficients(), weierstrass.coefficients());
+        assert_eq!(back.montgomery_curve().j_invariant(), montgomery.j_invariant());
+        for _ in 0..10 {
+            let (p, q) = (montgomery.rand(), montgomery.rand());
+            let (p_w, q_w) = (map.to_weierstrass(&p), map.to_weierstrass(&q));
+            assert!(p_w.is_on_curve());
+            assert_eq!(p_w.clone() + q_w.clone(), map.to_weierstrass(&(p.clone() + q.clone())));
+            assert_eq!(map.to_montgomery(&p_w), p);
+
+            let p_m = back.to_montgomery(&p_w);
+            assert!(p_m.is_on_curve());
+            assert_eq!(back.to_montgomery(&(p_w.clone() + q_w.clone())), p_m + back.to_montgomery(&q_w));
+            assert_eq!(back.to_weierstrass(&back.to_montgomery(&p_w)), p_w);
+        }
+        assert!(map.to_weierstrass(&montgomery.zero()).is_zero());
+        assert!(back.to_montgomery(&weierstrass.zero()).is_zero());
+
+        // the twist with B = 2 + i goes to another Weierstrass curve
+        let twist = MontgomeryCurve::new_with_b(&fp2, c(3, 6), c(2, 1));
+        let map = twist.weierstrass_map();
+        for _ in 0..10 {
+            let (p, q) = (twist.rand(), twist.rand());
+            let p_w = map.to_weierstrass(&p);
+            assert!(p_w.is_on_curve());
+            assert_eq!(p_w + map.to_weierstrass(&q), map.to_weierstrass(&(p.clone() + q)));
+            assert_eq!(map.to_montgomery(&map.to_weierstrass(&p)), p);
+        }
+
+        // over F_13, the root 12 of x^3 + x + 2 gives 3 * 12^2 + 1 = 4, a square but not a fourth power, so B isn't 1
+        let fp = FiniteField::new(&BigUint::from(13_u32));
+        let c = |n : i64| fp.gen(&BigInt::from(n));
+        let weierstrass = WeierstrassCurve::new(&fp, c(1), c(2));
+        let map = weierstrass.montgomery_map().unwrap();
+        assert_ne!(map.montgomery_curve().coefficients().1, &fp.one());
+        let points = all_points(&weierstrass);
+        for p in &points {
+            let p_m = map.to_montgomery(p);
+            assert!(p_m.is_on_curve());
+            assert_eq!(map.to_weierstrass(&p_m), *p);
+            for q in &points {
+                assert_eq!(map.to_montgomery(&(p + q)), p_m.clone() + map.to_montgomery(q));
+            }
+        }
+
+        // x^3 + 2 has no root and the root 7 of x^3 + x + 1 gives 3 * 7^2 + 1 = 5, which is not a square
+        assert!(WeierstrassCurve::new(&fp, c(0), c(2)).montgomery_map().is_none());
+        assert!(WeierstrassCurve::new(&fp, c(1), c(1)).montgomery_map().is_none());
+    }
+}
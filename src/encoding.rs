@@ -0,0 +1,326 @@
+//! Canonical byte encodings of field elements, curves and points
+//!
+//! Integers modulo p are encoded in little-endian with the fixed length of p in bytes.
+//! Elements of F_{p^2} are encoded as a || b for a + b * i, following the SIKE reference implementation.
+//! The decoding is strict : wrong lengths and non-reduced integers are rejected.
+use std::fmt;
+use num::BigUint;
+use crate::field::FieldEncoding;
+
+/// Error of the strict decoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input doesn't have the length of the encoding
+    InvalidLength { expected : usize, found : usize },
+    /// The input isn't a hexadecimal string
+    InvalidHex,
+    /// The encoded integer isn't reduced or a flag is invalid
+    NonCanonical,
+    /// The coordinates don't satisfy the curve equation
+    NotOnCurve,
+    /// The coefficient defines a singular curve
+    SingularCurve,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength { expected, found } => write!(f, "expected {expected} bytes but found {found} bytes"),
+            DecodeError::InvalidHex => write!(f, "invalid hexadecimal string"),
+            DecodeError::NonCanonical => write!(f, "non-canonical encoding"),
+            DecodeError::NotOnCurve => write!(f, "the point is not on the curve"),
+            DecodeError::SingularCurve => write!(f, "the curve is singular"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Encode bytes as a lowercase hexadecimal string
+pub fn encode_hex(bytes : &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Decode a hexadecimal string without prefix
+pub fn decode_hex(hex : &str) -> Result<Vec<u8>, DecodeError> {
+    if hex.len() % 2 == 1 {
+        return Err(DecodeError::InvalidHex);
+    }
+
+    hex.as_bytes().chunks(2).map(|pair| {
+        let digits = std::str::from_utf8(pair).map_err(|_| DecodeError::InvalidHex)?;
+        match digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            true => u8::from_str_radix(digits, 16).map_err(|_| DecodeError::InvalidHex),
+            false => Err(DecodeError::InvalidHex),
+        }
+    }).collect()
+}
+
+/// Check the length of an encoding
+pub(crate) fn check_len(bytes : &[u8], expected : usize) -> Result<(), DecodeError> {
+    match bytes.len() == expected {
+        true => Ok(()),
+        false => Err(DecodeError::InvalidLength { expected, found : bytes.len() }),
+    }
+}
+
+/// The length of the integers modulo p in bytes
+pub(crate) fn modulus_len(modulus : &BigUint) -> usize {
+    (modulus.bits() as usize).div_ceil(8)
+}
+
+/// Encode an integer in little-endian with the fixed length
+pub(crate) fn uint_to_bytes(num : &BigUint, len : usize) -> Vec<u8> {
+    let mut bytes = num.to_bytes_le();
+    bytes.resize(len, 0);
+    bytes
+}
+
+/// Decode a little-endian integer which must be less than the modulus
+pub(crate) fn uint_from_bytes(bytes : &[u8], modulus : &BigUint) -> Result<BigUint, DecodeError> {
+    check_len(bytes, modulus_len(modulus))?;
+    let num = BigUint::from_bytes_le(bytes);
+    match num < *modulus {
+        true => Ok(num),
+        false => Err(DecodeError::NonCanonical),
+    }
+}
+
+/// Types which decode canonical encodings into values, like fields into their elements
+pub trait Decoder {
+    type Output;
+    fn decode(&self, bytes : &[u8]) -> Result<Self::Output, DecodeError>;
+}
+
+/// Fields decode their elements
+impl<F : FieldEncoding> Decoder for F {
+    type Output = F::Element;
+    fn decode(&self, bytes : &[u8]) -> Result<Self::Output, DecodeError> {
+        self.element_from_bytes(bytes)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use self::serde_impls::DecodeSeed;
+
+/// [serde](https://serde.rs) support
+///
+/// The values are serialized as hexadecimal strings of their encodings for human-readable formats and as bytes otherwise.
+/// The elements of fields with runtime parameters are deserialized with a [DecodeSeed](DecodeSeed) holding their field.
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::*;
+    use crate::field::ElementEncoding;
+    use crate::field::fp::FiniteFieldElement;
+    use crate::field::fp2::FiniteField2Element;
+    use crate::field::fp_mont::MontgomeryFieldElement;
+    use crate::field::fp_const::{PrimeFieldParams, ConstPrimeField, ConstPrimeFieldElement, ConstField2, ConstField2Element};
+    use crate::field::extension::ExtensionFieldElement;
+    use crate::elliptic_curve::mont_curve::{MontgomeryCurve, MontgomeryCurvePoint};
+    use serde::{Serialize, Serializer, Deserialize, Deserializer};
+    use serde::de::{self, DeserializeSeed, Visitor};
+
+    fn serialize_bytes<S : Serializer>(bytes : &[u8], serializer : S) -> Result<S::Ok, S::Error> {
+        match serializer.is_human_readable() {
+            true => serializer.serialize_str(&encode_hex(bytes)),
+            false => serializer.serialize_bytes(bytes),
+        }
+    }
+
+    macro_rules! impl_serialize {
+        ($([$($param:tt)*] $ty:ty;)*) => {$(
+            impl<$($param)*> Serialize for $ty {
+                fn serialize<S : Serializer>(&self, serializer : S) -> Result<S::Ok, S::Error> {
+                    serialize_bytes(&self.to_bytes(), serializer)
+                }
+            }
+        )*};
+    }
+
+    impl_serialize! {
+        [] FiniteFieldElement;
+        [] FiniteField2Element;
+        [const N: usize] MontgomeryFieldElement<N>;
+        [P : PrimeFieldParams<N>, const N: usize] ConstPrimeFieldElement<P, N>;
+        [P : PrimeFieldParams<N>, const N: usize] ConstField2Element<P, N>;
+        [F : FieldEncoding] ExtensionFieldElement<F>;
+        [F : FieldEncoding] MontgomeryCurve<F>;
+        [F : FieldEncoding] MontgomeryCurvePoint<F>;
+    }
+
+    /// Deserialize the encodings with a [Decoder](Decoder), e.g. a field or a curve
+    pub struct DecodeSeed<'a, D : Decoder>(pub &'a D);
+
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a hexadecimal string or bytes")
+        }
+
+        fn visit_str<E : de::Error>(self, hex : &str) -> Result<Self::Value, E> {
+            decode_hex(hex).map_err(E::custom)
+        }
+
+        fn visit_bytes<E : de::Error>(self, bytes : &[u8]) -> Result<Self::Value, E> {
+            Ok(bytes.to_vec())
+        }
+
+        fn visit_seq<A : de::SeqAccess<'de>>(self, mut seq : A) -> Result<Self::Value, A::Error> {
+            let mut bytes = vec![];
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            Ok(bytes)
+        }
+    }
+
+    fn deserialize_bytes<'de, De : Deserializer<'de>>(deserializer : De) -> Result<Vec<u8>, De::Error> {
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_str(BytesVisitor),
+            false => deserializer.deserialize_bytes(BytesVisitor),
+        }
+    }
+
+    impl<'de, D : Decoder> DeserializeSeed<'de> for DecodeSeed<'_, D> {
+        type Value = D::Output;
+
+        fn deserialize<De : Deserializer<'de>>(self, deserializer : De) -> Result<Self::Value, De::Error> {
+            let bytes = deserialize_bytes(deserializer)?;
+            self.0.decode(&bytes).map_err(de::Error::custom)
+        }
+    }
+
+    impl<'de, P : PrimeFieldParams<N>, const N: usize> Deserialize<'de> for ConstPrimeFieldElement<P, N> {
+        fn deserialize<De : Deserializer<'de>>(deserializer : De) -> Result<Self, De::Error> {
+            DecodeSeed(&ConstPrimeField::<P, N>::new()).deserialize(deserializer)
+        }
+    }
+
+    impl<'de, P : PrimeFieldParams<N>, const N: usize> Deserialize<'de> for ConstField2Element<P, N> {
+        fn deserialize<De : Deserializer<'de>>(deserializer : De) -> Result<Self, De::Error> {
+            DecodeSeed(&ConstField2::<P, N>::new()).deserialize(deserializer)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::field::Field;
+        use crate::field::fp::FiniteField;
+        use crate::field::params::Fp434Sqr;
+        use num::BigInt;
+
+        #[test]
+        fn serde_test() {
+            let fp = FiniteField::new(&BigUint::from(1000003_u32));
+            let a = fp.gen(&BigInt::from(123456));
+            let json = serde_json::to_string(&a).unwrap();
+            assert_eq!(json, "\"40e201\"");
+            let mut deserializer = serde_json::Deserializer::from_str(&json);
+            assert_eq!(DecodeSeed(&fp).deserialize(&mut deserializer).unwrap(), a);
+
+            let curve = MontgomeryCurve::new(&fp, fp.gen(&BigInt::from(6)));
+            let point = curve.rand();
+            let json = serde_json::to_string(&point).unwrap();
+            let mut deserializer = serde_json::Deserializer::from_str(&json);
+            assert_eq!(DecodeSeed(&curve).deserialize(&mut deserializer).unwrap(), point);
+
+            let b = Fp434Sqr::new().rand(None);
+            let json = serde_json::to_string(&b).unwrap();
+            assert_eq!(serde_json::from_str::<ConstField2Element<_, 7>>(&json).unwrap(), b);
+            assert!(serde_json::from_str::<ConstField2Element<crate::field::params::P434, 7>>("\"00\"").is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Field, ElementEncoding};
+    use crate::field::fp::FiniteField;
+    use crate::field::fp2::FiniteField2;
+    use crate::field::fp_mont::MontgomeryField;
+    use crate::field::extension::ExtensionField;
+    use crate::field::params::{Fp434, Fp434Sqr};
+    use crate::elliptic_curve::mont_curve::MontgomeryCurve;
+    use crate::group::Group;
+    use crate::poly::Polynomial;
+    use num::{BigInt, One};
+
+    #[test]
+    fn field_encoding_test() {
+        let prime = BigUint::from(1000003_u32);
+        let fp = FiniteField::new(&prime);
+        let fp_mont = MontgomeryField::<1>::new(&prime);
+        assert_eq!(fp.byte_len(), 3);
+
+        let a = fp.gen(&BigInt::from(123456));
+        assert_eq!(a.to_bytes(), vec![0x40, 0xe2, 0x01]);
+        assert_eq!(a.to_hex(), "40e201");
+        assert_eq!(fp.element_from_hex("40e201").unwrap(), a);
+        assert_eq!(fp_mont.gen(&BigInt::from(123456)).to_bytes(), a.to_bytes());
+
+        // p itself and wrong lengths are rejected
+        assert_eq!(fp.element_from_bytes(&prime.to_bytes_le()), Err(DecodeError::NonCanonical));
+        assert_eq!(fp_mont.element_from_bytes(&prime.to_bytes_le()).map(|x| x.to_bytes()), Err(DecodeError::NonCanonical));
+        assert_eq!(fp.element_from_bytes(&[0x40, 0xe2]), Err(DecodeError::InvalidLength { expected : 3, found : 2 }));
+        assert_eq!(fp.element_from_hex("40e2010"), Err(DecodeError::InvalidHex));
+        assert_eq!(fp.element_from_hex("0x40e2"), Err(DecodeError::InvalidHex));
+
+        // a || b
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(1000002_u32));
+        let b = fp2.gen((&BigInt::from(123456), &BigInt::one()));
+        assert_eq!(b.to_hex(), "40e201010000");
+        assert_eq!(fp2.element_from_bytes(&b.to_bytes()).unwrap(), b);
+
+        // SIKE p434 elements take 55 bytes
+        let (fp434, fp434_sqr) = (Fp434::new(), Fp434Sqr::new());
+        assert_eq!(fp434.byte_len(), 55);
+        assert_eq!(fp434_sqr.byte_len(), 110);
+        let c = fp434_sqr.rand(None);
+        assert_eq!(fp434_sqr.element_from_hex(&c.to_hex()).unwrap(), c);
+        assert_eq!(fp434.element_from_bytes(&fp434.prime().to_bytes_le()), Err(DecodeError::NonCanonical));
+
+        let fq = ExtensionField::new(&fp, &Polynomial::new(&fp, vec![fp.gen(&BigInt::from(-2)), fp.zero(), fp.zero(), fp.one()]));
+        let d = fq.embed(&a);
+        assert_eq!(d.to_bytes().len(), 9);
+        assert_eq!(fq.element_from_bytes(&d.to_bytes()).unwrap(), d);
+    }
+
+    #[test]
+    fn curve_encoding_test() {
+        let fp = FiniteField::new(&BigUint::from(1000003_u32));
+        let curve = MontgomeryCurve::new(&fp, fp.gen(&BigInt::from(6)));
+        assert_eq!(MontgomeryCurve::from_hex(&fp, &curve.to_hex()).unwrap().to_bytes(), curve.to_bytes());
+        assert!(matches!(MontgomeryCurve::from_bytes(&fp, &fp.gen(&BigInt::from(2)).to_bytes()), Err(DecodeError::SingularCurve)));
+
+        // B != 1 follows A, and B = 1 is only canonical when it's omitted
+        let twist = MontgomeryCurve::new_with_b(&fp, fp.gen(&BigInt::from(6)), fp.gen(&BigInt::from(5)));
+        assert_eq!(twist.to_bytes().len(), 6);
+        assert_eq!(MontgomeryCurve::from_bytes(&fp, &twist.to_bytes()).unwrap().coefficients(), twist.coefficients());
+        let explicit_one = [fp.gen(&BigInt::from(6)).to_bytes(), fp.one().to_bytes()].concat();
+        assert!(matches!(MontgomeryCurve::from_bytes(&fp, &explicit_one), Err(DecodeError::NonCanonical)));
+        let twist_point = twist.rand();
+        assert_eq!(twist.point_from_bytes(&twist_point.to_bytes()), Ok(twist_point.clone()));
+        assert_eq!(curve.point_from_bytes(&twist_point.to_bytes()), Err(DecodeError::NotOnCurve));
+
+        let point = curve.rand() * BigInt::from(3);
+        let bytes = point.to_bytes();
+        assert_eq!(bytes.len(), 7);
+        assert_eq!(curve.point_from_bytes(&bytes).unwrap(), point);
+        assert_eq!(curve.point_from_hex(&point.to_hex()).unwrap(), point);
+        assert_eq!(curve.zero().to_bytes(), vec![0; 7]);
+        assert_eq!(curve.point_from_bytes(&[0; 7]).unwrap(), curve.zero());
+
+        let mut invalid = bytes.clone();
+        invalid[4] ^= 1;
+        assert!(matches!(curve.point_from_bytes(&invalid), Err(DecodeError::NotOnCurve)));
+        invalid = bytes.clone();
+        invalid[0] = 2;
+        assert!(matches!(curve.point_from_bytes(&invalid), Err(DecodeError::NonCanonical)));
+        assert!(matches!(curve.point_from_bytes(&[0, 0, 0, 0, 0, 0, 1]), Err(DecodeError::NonCanonical)));
+    }
+}
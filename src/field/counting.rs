@@ -0,0 +1,201 @@
+//! Field wrapper counting the field operations
+//! The tests of the constant-time algorithms check that the counts don't depend on the secret
+use super::*;
+use std::cell::Cell;
+use std::ops::{Mul, Neg};
+use subtle::Choice;
+use impl_ops::impl_bin_ops;
+use rand::RngCore;
+
+/// The number of each field operation since the last [reset](reset)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct OperationCount {
+    pub add : usize,
+    pub sub : usize,
+    pub mul : usize,
+    pub neg : usize,
+    pub inv : usize,
+    pub select : usize,
+    pub eq : usize,
+}
+
+thread_local! {
+    static COUNT : Cell<OperationCount> = Cell::new(OperationCount::default());
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Neg,
+    Inv,
+    Select,
+    Eq,
+}
+
+fn record(operation : Operation) {
+    COUNT.with(|count| {
+        let mut tmp = count.get();
+        match operation {
+            Operation::Add => tmp.add += 1,
+            Operation::Sub => tmp.sub += 1,
+            Operation::Mul => tmp.mul += 1,
+            Operation::Neg => tmp.neg += 1,
+            Operation::Inv => tmp.inv += 1,
+            Operation::Select => tmp.select += 1,
+            Operation::Eq => tmp.eq += 1,
+        }
+        count.set(tmp);
+    });
+}
+
+pub(crate) fn reset() {
+    COUNT.with(|count| count.set(OperationCount::default()));
+}
+
+pub(crate) fn count() -> OperationCount {
+    COUNT.with(|count| count.get())
+}
+
+/// Field whose elements count their operations
+#[derive(Debug, Clone)]
+pub(crate) struct CountingField<F : Field> {
+    pub field : F,
+}
+
+impl<F : Field> Field for CountingField<F> {
+    type Element = Counting<F::Element>;
+    fn zero(&self) -> Self::Element {
+        Counting(self.field.zero())
+    }
+    fn one(&self) -> Self::Element {
+        Counting(self.field.one())
+    }
+    fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R, size : Option<usize>) -> Self::Element {
+        Counting(self.field.rand_with_rng(rng, size))
+    }
+    fn characteristic(&self) -> BigInt {
+        self.field.characteristic()
+    }
+    fn extension_degree(&self) -> usize {
+        self.field.extension_degree()
+    }
+    fn order(&self) -> BigInt {
+        self.field.order()
+    }
+}
+
+impl<F : FieldParsing> FieldParsing for CountingField<F> {
+    fn element_from_str(&self, s : &str) -> Result<Self::Element, ParseError> {
+        self.field.element_from_str(s).map(Counting)
+    }
+}
+
+impl<F : FieldEncoding> FieldEncoding for CountingField<F> {
+    fn byte_len(&self) -> usize {
+        self.field.byte_len()
+    }
+    fn element_from_bytes(&self, bytes : &[u8]) -> Result<Self::Element, DecodeError> {
+        self.field.element_from_bytes(bytes).map(Counting)
+    }
+}
+
+impl<F : Field + fmt::Display> fmt::Display for CountingField<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.field)
+    }
+}
+
+/// Field element counting its operations
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Counting<T : FieldElement>(pub T);
+
+impl<T : FieldElement + fmt::Display> fmt::Display for Counting<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<T : FieldElement> FieldElement for Counting<T> {
+    fn inv(&self) -> Self {
+        record(Operation::Inv);
+        Counting(self.0.inv())
+    }
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+    fn pow(&self, exponent : &BigInt) -> Self {
+        Counting(self.0.pow(exponent))
+    }
+    fn sqrt(&self) -> Option<Self> {
+        self.0.sqrt().map(Counting)
+    }
+    fn sqrt_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> Option<Self> {
+        self.0.sqrt_with_rng(rng).map(Counting)
+    }
+}
+
+impl<T : ElementEncoding> ElementEncoding for Counting<T> {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+}
+
+impl<T : NthRoot> NthRoot for Counting<T> {
+    fn nth_root_with_rng<R : RngCore + ?Sized>(&self, n : u64, rng : &mut R) -> Option<Self> {
+        self.0.nth_root_with_rng(n, rng).map(Counting)
+    }
+}
+
+impl<T : ConditionallySelectable> ConditionallySelectable for Counting<T> {
+    fn conditional_select(a : &Self, b : &Self, choice : Choice) -> Self {
+        record(Operation::Select);
+        Counting(T::conditional_select(&a.0, &b.0, choice))
+    }
+
+    fn ct_eq(&self, rhs : &Self) -> Choice {
+        record(Operation::Eq);
+        self.0.ct_eq(&rhs.0)
+    }
+}
+
+impl<T : FieldElement> Neg for Counting<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        record(Operation::Neg);
+        Counting(-self.0)
+    }
+}
+
+#[impl_bin_ops]
+impl<T : FieldElement> Add for Counting<T> {
+    fn add(self, rhs : Counting<T>) -> Counting<T> {
+        record(Operation::Add);
+        Counting(self.0.clone() + rhs.0.clone())
+    }
+}
+
+#[impl_bin_ops]
+impl<T : FieldElement> Sub for Counting<T> {
+    fn sub(self, rhs : Counting<T>) -> Counting<T> {
+        record(Operation::Sub);
+        Counting(self.0.clone() - rhs.0.clone())
+    }
+}
+
+#[impl_bin_ops]
+impl<T : FieldElement> Mul for Counting<T> {
+    fn mul(self, rhs : Counting<T>) -> Counting<T> {
+        record(Operation::Mul);
+        Counting(self.0.clone() * rhs.0.clone())
+    }
+}
+
+impl<T : FieldElement> Mul<BigInt> for Counting<T> {
+    type Output = Self;
+    fn mul(self, rhs : BigInt) -> Self {
+        record(Operation::Mul);
+        Counting(self.0 * rhs)
+    }
+}
@@ -0,0 +1,398 @@
+//! Implementation of finite field extensions F_q[x] / (f(x)) for an irreducible polynomial f
+use core::fmt;
+use super::*;
+use crate::poly::Polynomial;
+use std::ops::{Mul, Neg};
+use num::{BigInt, Integer};
+use num::bigint::Sign;
+use impl_ops::impl_bin_ops;
+use std::sync::Arc;
+use rand::RngCore;
+
+/// Type for an extension of a finite field defined by an irreducible polynomial
+/// The elements are the polynomials of degree less than the degree of the modulus
+#[derive(Debug, Clone)]
+pub struct ExtensionField<F> where F: Field {
+    base_field : F,
+    modulus : Arc<Polynomial<F>>,
+}
+
+impl<F> ExtensionField<F> where F: Field {
+    /// The modulus must be irreducible over the base field, it's not checked
+    /// It's made monic and its degree must be at least 1 or it will panic
+    pub fn new(base_field : &F, modulus : &Polynomial<F>) -> Self {
+        let mut modulus = modulus.clone();
+        modulus.normalize();
+        assert!(modulus.degree() >= 1, "The modulus must have degree at least 1!");
+
+        let lc_inv = modulus.coefficients().last().unwrap().inv();
+        let coefficient = modulus.coefficients().iter().map(|c| c.clone() * lc_inv.clone()).collect();
+
+        ExtensionField {
+            base_field : base_field.clone(),
+            modulus : Arc::new(Polynomial::new(base_field, coefficient)),
+        }
+    }
+
+    /// Output an initialized [ExtensionFieldElement](ExtensionFieldElement)
+    /// The coefficients c_0, c_1, ... represent c_0 + c_1 * x + ... modulo the modulus
+    pub fn gen(&self, coefficient : Vec<F::Element>) -> ExtensionFieldElement<F> {
+        let mut poly = Polynomial::new(&self.base_field, coefficient);
+        poly.normalize();
+        self.element(poly)
+    }
+
+    /// Output the class of x
+    pub fn generator(&self) -> ExtensionFieldElement<F> {
+        self.gen(vec![self.base_field.zero(), self.base_field.one()])
+    }
+
+    /// Output the base field
+    pub fn base_field(&self) -> &F {
+        &self.base_field
+    }
+
+    /// Output the monic modulus
+    pub fn modulus(&self) -> &Polynomial<F> {
+        &self.modulus
+    }
+
+    /// The degree of the extension
+    pub fn degree(&self) -> usize {
+        self.modulus.degree()
+    }
+
+    /// Embed an element of the base field
+    pub fn embed(&self, a : &F::Element) -> ExtensionFieldElement<F> {
+        self.gen(vec![a.clone()])
+    }
+
+    /// The reduction modulo the modulus of a normalized polynomial
+    fn element(&self, poly : Polynomial<F>) -> ExtensionFieldElement<F> {
+        let poly = match poly.degree() < self.degree() {
+            true => poly,
+            false => poly % (*self.modulus).clone(),
+        };
+        ExtensionFieldElement { field : self.clone(), poly }
+    }
+}
+
+/// ExtensionField type is a [Field](Field)
+impl<F> Field for ExtensionField<F> where F: Field {
+    type Element = ExtensionFieldElement<F>;
+    /// Implement DynZero for [ExtensionField](ExtensionField)
+    fn zero(&self) -> Self::Element {
+        self.gen(vec![])
+    }
+    /// Implement DynOne for [ExtensionField](ExtensionField)
+    fn one(&self) -> Self::Element {
+        self.gen(vec![self.base_field.one()])
+    }
+
+    /// Generate a random element
+    fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R, size : Option<usize>) -> Self::Element {
+        self.gen((0..self.degree()).map(|_| self.base_field.rand_with_rng(rng, size)).collect())
+    }
+
+    /// Characteristic
+    fn characteristic(&self) -> BigInt {
+        self.base_field.characteristic()
+    }
+
+    fn extension_degree(&self) -> usize {
+        self.base_field.extension_degree() * self.degree()
+    }
+}
+
+impl<F> FieldParsing for ExtensionField<F> where F: FieldParsing {
+    /// Parse a polynomial in z whose coefficients are parsed by the base field
+    fn element_from_str(&self, s : &str) -> Result<Self::Element, ParseError> {
+        Ok(self.element(Polynomial::parse_in(&self.base_field, s, "z")?))
+    }
+}
+
+impl<F> FieldEncoding for ExtensionField<F> where F: FieldEncoding {
+    fn byte_len(&self) -> usize {
+        self.degree() * self.base_field.byte_len()
+    }
+
+    /// Decode c_0 || c_1 || ... for c_0 + c_1 * x + ...
+    fn element_from_bytes(&self, bytes : &[u8]) -> Result<Self::Element, DecodeError> {
+        encoding::check_len(bytes, self.byte_len())?;
+        let coefficient = bytes.chunks(self.base_field.byte_len())
+            .map(|chunk| self.base_field.element_from_bytes(chunk))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.gen(coefficient))
+    }
+}
+
+/// The elements are written as polynomials in z
+impl<F> fmt::Display for ExtensionField<F> where F: Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // order = p^k
+        let p = self.characteristic();
+        let mut order = self.order();
+        let mut k = 0;
+        while order > BigInt::one() {
+            order /= &p;
+            k += 1;
+        }
+        write!(f, "Finite Field in z of size {p}^{k}")
+    }
+}
+
+/// ExtensionFieldElement type for the elements in [ExtensionField](ExtensionField)
+/// It holds the reduced polynomial representing it
+#[derive(Clone)]
+pub struct ExtensionFieldElement<F> where F: Field {
+    field : ExtensionField<F>,
+    poly : Polynomial<F>,
+}
+
+impl<F> fmt::Debug for ExtensionFieldElement<F> where F: Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.poly)
+    }
+}
+
+impl<F> fmt::Display for ExtensionFieldElement<F> where F: Field, F::Element: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.poly.fmt_in(f, "z")
+    }
+}
+
+impl<F> ExtensionFieldElement<F> where F: Field {
+    /// The coefficients of the reduced polynomial from the constant term
+    /// Leading zeros are omitted
+    pub fn coefficients(&self) -> &[F::Element] {
+        self.poly.coefficients()
+    }
+
+    /// The p-power Frobenius where p is the characteristic
+    pub fn frobenius(&self) -> Self {
+        self.pow(&self.field.characteristic())
+    }
+
+    /// Whether it's in the base field or not
+    pub fn is_in_base_field(&self) -> bool {
+        self.poly.degree() == 0
+    }
+}
+
+impl<F> FieldElement for ExtensionFieldElement<F> where F: Field {
+    /// Lagrange's theorem : a^{-1} = a^{q-2} for the order q of the field
+    fn inv(&self) -> Self {
+        assert!(!self.is_zero(), "There is no multiplicative inverse of {self:?}");
+        self.pow(&(self.field.order() - 2))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.poly.is_zero()
+    }
+
+    /// Compute the power operation
+    fn pow(&self, exponent : &BigInt) -> Self {
+        let mut result = self.field.one();
+        let mut tmp_value = self.clone();
+
+        for digit in exponent.iter_u32_digits() {
+            for i in 0..32 {
+                if (digit >> i) & 1 == 1 {
+                    result = result.clone() * tmp_value.clone();
+                }
+                tmp_value = tmp_value.clone() * tmp_value.clone();
+            }
+        }
+        match exponent.sign() {
+            Sign::Minus => result.inv(),
+            _ => result,
+        }
+    }
+
+    /// Euler's criterion : a^{(q-1)/2} = 1, every element is a square in characteristic 2
+    fn is_square(&self) -> bool {
+        let order = self.field.order();
+        order.is_even() || self.is_zero() || self.pow(&((order - 1) >> 1)) == self.field.one()
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        self.sqrt_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Compute the square root using Tonelli-Shanks algorithm with a random non-residue
+    /// In characteristic 2 it's a^{q/2}
+    fn sqrt_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> Option<Self> {
+        let order = self.field.order();
+        if order.is_even() {
+            return Some(self.pow(&(order >> 1)));
+        }
+
+        if !self.is_square() {
+            return None;
+        }
+
+        let non_residue = loop {
+            let c = self.field.rand_with_rng(rng, None);
+            if !c.is_square() {
+                break c;
+            }
+        };
+        tonelli_shanks(self, &non_residue, &order)
+    }
+}
+
+impl<F> ElementEncoding for ExtensionFieldElement<F> where F: FieldEncoding {
+    /// Encode c_0 + c_1 * x + ... as c_0 || c_1 || ... with all the coefficients below the degree
+    fn to_bytes(&self) -> Vec<u8> {
+        let zero = self.field.base_field.zero();
+        (0..self.field.degree())
+            .flat_map(|i| self.coefficients().get(i).unwrap_or(&zero).to_bytes())
+            .collect()
+    }
+}
+
+impl<F> NthRoot for ExtensionFieldElement<F> where F: FieldEncoding {
+    fn nth_root_with_rng<R : RngCore + ?Sized>(&self, n : u64, rng : &mut R) -> Option<Self> {
+        nth_root(&self.field, self, n, rng)
+    }
+}
+
+impl<F> Neg for ExtensionFieldElement<F> where F: Field {
+    type Output = Self;
+    fn neg(self) -> Self {
+        ExtensionFieldElement { field : self.field, poly : -self.poly }
+    }
+}
+
+impl<F> PartialEq for ExtensionFieldElement<F> where F: Field {
+    fn eq(&self, rhs:&Self) -> bool {
+        assert!(Arc::ptr_eq(&self.field.modulus, &rhs.field.modulus) || self.field.modulus.coefficients() == rhs.field.modulus.coefficients(), "The base field is not equal");
+        self.poly.coefficients() == rhs.poly.coefficients()
+    }
+}
+
+#[impl_bin_ops]
+impl<F> Add for ExtensionFieldElement<F> where F: Field {
+    fn add(self, rhs : ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+        ExtensionFieldElement { field : self.field.clone(), poly : self.poly.clone() + rhs.poly.clone() }
+    }
+}
+
+#[impl_bin_ops]
+impl<F> Sub for ExtensionFieldElement<F> where F: Field {
+    fn sub(self, rhs : ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+        ExtensionFieldElement { field : self.field.clone(), poly : self.poly.clone() - rhs.poly.clone() }
+    }
+}
+
+#[impl_bin_ops]
+impl<F> Mul for ExtensionFieldElement<F> where F: Field {
+    fn mul(self, rhs : ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+        self.field.element(self.poly.clone() * rhs.poly.clone())
+    }
+}
+
+/// Scalar multiplication as Z-module
+impl<F> Mul<BigInt> for ExtensionFieldElement<F> where F: Field {
+    type Output = Self;
+    fn mul(self, rhs : BigInt) -> Self {
+        self.field.gen(self.poly.coefficients().iter().map(|c| c.clone() * rhs.clone()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::field::fp::FiniteField;
+    use crate::field::fp2::FiniteField2;
+    use crate::elliptic_curve::mont_curve::MontgomeryCurve;
+    use crate::group::GroupElement;
+    use num::BigUint;
+
+    /// F_p[x] / (x^3 - c) for a non-cube c, p = 1 mod 3
+    fn cubic_extension(prime_num : u32) -> ExtensionField<FiniteField> {
+        let fp = FiniteField::new(&BigUint::from(prime_num));
+        let c = (2..prime_num).map(|c| fp.gen(&BigInt::from(c)))
+            .find(|c| c.cube_root().is_none()).unwrap();
+        ExtensionField::new(&fp, &Polynomial::new(&fp, vec![-c, fp.zero(), fp.zero(), fp.one()]))
+    }
+
+    #[test]
+    fn extension_field_test() {
+        let fq = cubic_extension(103);
+        let p = fq.characteristic();
+        assert_eq!(fq.order(), BigInt::from(103).pow(3));
+
+        for _ in 0..20 {
+            let (a, b, c) = (fq.rand(None), fq.rand(None), fq.rand(None));
+            assert_eq!(&a * (&b + &c), &a * &b + &a * &c);
+            assert_eq!(a.frobenius(), a.pow(&p));
+            assert_eq!(a.frobenius().frobenius().frobenius(), a);
+            assert_eq!((&a + &b).frobenius(), a.frobenius() + b.frobenius());
+            assert_eq!(a.pow(&fq.order()), a);
+
+            if !a.is_zero() {
+                assert_eq!(&a * a.inv(), fq.one());
+            }
+
+            let square = &a * &a;
+            let root = square.sqrt().unwrap();
+            assert_eq!(&root * &root, square);
+            assert_eq!(a.is_square(), a.sqrt().is_some());
+
+            let cube = &a * &a * &a;
+            let root = cube.cube_root().unwrap();
+            assert_eq!(&root * &root * &root, cube);
+        }
+
+        // the norm of x is c
+        let x = fq.generator();
+        let norm = &x * x.frobenius() * x.frobenius().frobenius();
+        assert!(norm.is_in_base_field());
+        assert_eq!(x.pow(&BigInt::from(3)), norm);
+    }
+
+    #[test]
+    fn tower_extension_test() {
+        // F_{p^4} = F_{p^2}[y] / (y^2 - beta) for a non-square beta in F_{p^2}
+        let fp = FiniteField::new(&BigUint::from(19_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(18_u32));
+        let beta = loop {
+            let beta = fp2.rand(None);
+            if !beta.is_square() {
+                break beta;
+            }
+        };
+        let fp4 = ExtensionField::new(&fp2, &Polynomial::new(&fp2, vec![-beta.clone(), fp2.zero(), fp2.one()]));
+        assert_eq!(fp4.extension_degree(), 4);
+        assert_eq!(fp4.order(), BigInt::from(19).pow(4));
+
+        let y = fp4.generator();
+        assert_eq!(&y * &y, fp4.embed(&beta));
+        assert_eq!(fp4.embed(&beta).sqrt().map(|root| &root * &root), Some(fp4.embed(&beta)));
+        for _ in 0..20 {
+            let a = fp4.rand(None);
+            assert_eq!(a.frobenius(), a.pow(&BigInt::from(19)));
+            let root = (&a * &a).sqrt().unwrap();
+            assert_eq!(&root * &root, &a * &a);
+        }
+    }
+
+    #[test]
+    fn base_change_test() {
+        // y^2 = x^3 + x is supersingular over F_103 so it has p^3 + 1 points over F_{p^3}
+        let fq = cubic_extension(103);
+        let curve = MontgomeryCurve::new(fq.base_field(), fq.base_field().zero());
+        let curve_ext = curve.base_change(&fq);
+        let order : BigInt = BigInt::from(103).pow(3) + 1;
+
+        for _ in 0..5 {
+            let point = curve_ext.rand();
+            assert!((point * order.clone()).is_zero());
+        }
+
+        let point = curve.rand();
+        let point_ext = point.base_change(&fq);
+        assert!((point_ext * BigInt::from(104)).is_zero());
+    }
+}
@@ -1,6 +1,7 @@
 //! Implementation of finite fields using [num](https://crates.io/crates/num) library
 use core::fmt;
 use super::*;
+use super::hash_to_field::HashToField;
 use std::ops::{Mul, Neg};
 use num::integer::ExtendedGcd;
 use num::{BigInt, BigUint, Zero, Integer, One, Signed};
@@ -8,12 +9,14 @@ use num::bigint::{ToBigInt, Sign, RandomBits};
 use num_prime::buffer::NaiveBuffer;
 use num_prime::buffer::PrimeBufferExt;
 use impl_ops::impl_bin_ops;
-use rand::Rng;
+use rand::{Rng, RngCore};
+use std::sync::Arc;
 
 /// Type for a base of a finite field(extension degree = 1)
+/// It's a cheap handle to the shared prime, so elements can own a copy of it
 #[derive(Debug, Clone)]
 pub struct FiniteField {
-    prime : BigUint,
+    prime : Arc<BigUint>,
 }
 
 impl FiniteField {
@@ -23,7 +26,7 @@ impl FiniteField {
         assert!(pb.is_prime(prime, None).probably(), "The base number is not prime!");
         
         FiniteField {
-            prime : prime.clone()
+            prime : Arc::new(prime.clone())
         }
     }
 
@@ -35,32 +38,31 @@ impl FiniteField {
         }
 
         FiniteFieldElement{
-            field : self,
+            field : self.clone(),
             num : tmp.to_biguint().unwrap(),    
         }
     }
 
     /// Output its prime
-    fn prime(&self) -> BigUint {
-        self.prime.clone()
+    pub fn prime(&self) -> &BigUint {
+        &self.prime
     }
 }
 
 /// FiniteField type is a [Field](Field)
-impl<'a> Field<'a> for FiniteField {
-    type Element = FiniteFieldElement<'a>;
+impl Field for FiniteField {
+    type Element = FiniteFieldElement;
     /// Implement DynZero for [FiniteField](FiniteField)
-    fn zero(&'a self) -> Self::Element {
+    fn zero(&self) -> Self::Element {
         self.gen(&BigInt::zero())
     }
     /// Implement DynOne for [FiniteField](FiniteField)
-    fn one(&'a self) -> Self::Element {
+    fn one(&self) -> Self::Element {
         self.gen(&BigInt::one())
     }
 
     /// Generate a random element
-    fn rand(&'a self, size : Option<usize>) -> Self::Element {
-        let mut rng = rand::thread_rng();
+    fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R, size : Option<usize>) -> Self::Element {
         let num : BigInt = match size {
             Some(size) => {
                 rng.sample(RandomBits::new(size.try_into().unwrap()))   
@@ -75,25 +77,60 @@ impl<'a> Field<'a> for FiniteField {
 
     /// Characteristic
     fn characteristic(&self) -> BigInt {
-        self.prime.clone().to_bigint().unwrap()
+        self.prime.to_bigint().unwrap()
+    }
+}
+
+impl FieldParsing for FiniteField {
+    fn element_from_str(&self, s : &str) -> Result<Self::Element, ParseError> {
+        Ok(self.gen(&sage::parse_integer(s)?))
+    }
+}
+
+impl FieldEncoding for FiniteField {
+    fn byte_len(&self) -> usize {
+        encoding::modulus_len(self.prime())
+    }
+
+    fn element_from_bytes(&self, bytes : &[u8]) -> Result<Self::Element, DecodeError> {
+        let num = encoding::uint_from_bytes(bytes, self.prime())?;
+        Ok(FiniteFieldElement { field : self.clone(), num })
+    }
+}
+
+impl fmt::Display for FiniteField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Finite Field of size {}", self.prime)
+    }
+}
+
+impl HashToField for FiniteField {
+    fn element_from_coordinates(&self, coordinates : &[BigInt]) -> Self::Element {
+        self.gen(&coordinates[0])
     }
 }
 
 /// FiniteFieldElement type for the elements in [FiniteField](FiniteField)
-/// FiniteFieldElement refers to a [FiniteField](FiniteField)
+/// FiniteFieldElement owns a handle of its [FiniteField](FiniteField)
 #[derive(Clone)]
-pub struct FiniteFieldElement<'a> {
-    field : &'a FiniteField,
+pub struct FiniteFieldElement {
+    field : FiniteField,
     num : BigUint,
 }
 
-impl<'a> fmt::Debug for FiniteFieldElement<'a> {
+impl fmt::Debug for FiniteFieldElement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self.num)
     }
 }
 
-impl<'a> FieldElement for FiniteFieldElement<'a> {
+impl fmt::Display for FiniteFieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.num)
+    }
+}
+
+impl FieldElement for FiniteFieldElement {
     fn inv(&self) -> Self {
         let num_i = self.num.to_bigint().unwrap();
         let prime_i = self.field.prime().to_bigint().unwrap();
@@ -106,7 +143,7 @@ impl<'a> FieldElement for FiniteFieldElement<'a> {
         }
 
         FiniteFieldElement {
-            field : self.field,
+            field : self.field.clone(),
             num : x.to_biguint().unwrap(),
         }
     }
@@ -134,18 +171,32 @@ impl<'a> FieldElement for FiniteFieldElement<'a> {
         }
     }
 
-    /// Compute the square root using [Peralta's algorithm](https://arxiv.org/pdf/2206.07145.pdf)
+    fn is_square(&self) -> bool {
+        self.legendre() >= 0
+    }
+
+    /// The Legendre symbol (a / p) computed by the binary Jacobi symbol algorithm
+    fn legendre(&self) -> i8 {
+        jacobi(&self.num, self.field.prime())
+    }
+
     fn sqrt(&self) -> Option<Self> {
+        self.sqrt_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Compute the square root using [Peralta's algorithm](https://arxiv.org/pdf/2206.07145.pdf)
+    fn sqrt_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> Option<Self> {
         let (mut u, mut v) : (FiniteFieldElement, FiniteFieldElement);
         let prime = self.field.prime();
 
         // Check the quadratic residuosity
-        let check_exp : BigUint = (prime.clone() - BigUint::one()) >> 1;
-        if self.pow(&check_exp.to_bigint().unwrap()) != self.field.one() {
-            return None;
+        match self.legendre() {
+            0 => return Some(self.clone()),
+            -1 => return None,
+            _ => (),
         }
 
-        // the case of p=4k+3
+        // the case of p=4k+3 : a^{(p+1)/4} is the square root
         if prime.clone() % (4 as u32) == BigUint::from(3 as u32) {
             let exp : BigUint = (prime.clone() + BigUint::one()) >> 2;
             return Some(self.pow(&exp.to_bigint().unwrap()));
@@ -153,7 +204,7 @@ impl<'a> FieldElement for FiniteFieldElement<'a> {
         
         // Compute the square root
         loop {
-            u = self.field.rand(None);
+            u = self.field.rand_with_rng(rng, None);
             v = self.field.one();
 
             // u + 1 * \sqrt{-a}
@@ -200,49 +251,63 @@ impl<'a> FieldElement for FiniteFieldElement<'a> {
     }
 }
 
-impl<'a> Neg for FiniteFieldElement<'a> {
+impl ElementEncoding for FiniteFieldElement {
+    fn to_bytes(&self) -> Vec<u8> {
+        encoding::uint_to_bytes(&self.num, self.field.byte_len())
+    }
+}
+
+impl NthRoot for FiniteFieldElement {
+    fn nth_root_with_rng<R : RngCore + ?Sized>(&self, n : u64, rng : &mut R) -> Option<Self> {
+        nth_root(&self.field, self, n, rng)
+    }
+}
+
+impl Neg for FiniteFieldElement {
     type Output = Self;
     fn neg(self) -> Self {
-        FiniteFieldElement { field: self.field, num: self.field.prime() - self.num }
+        let num = (self.field.prime() - self.num) % self.field.prime();
+        FiniteFieldElement { field: self.field, num }
     }
 }
 
-impl<'a> PartialEq for FiniteFieldElement<'a> {
+impl PartialEq for FiniteFieldElement {
     fn eq(&self, rhs:&Self) -> bool {
-        assert!(self.field.prime() == rhs.field.prime(), "The base field is not equal");
+        assert!(Arc::ptr_eq(&self.field.prime, &rhs.field.prime) || self.field.prime == rhs.field.prime, "The base field is not equal");
         self.num == rhs.num
     }
 }
 
 #[impl_bin_ops]
-impl<'a> Add for FiniteFieldElement<'a> {
-    fn add(self, rhs : FiniteFieldElement<'a>) -> FiniteFieldElement<'a> {
-        let mut result: FiniteFieldElement<'_> = self.field.gen(&BigInt::zero());
-        result.num = (&self.num + &rhs.num) % &self.field.prime();
+impl Add for FiniteFieldElement {
+    fn add(self, rhs : FiniteFieldElement) -> FiniteFieldElement {
+        let mut result: FiniteFieldElement = self.field.gen(&BigInt::zero());
+        result.num = (&self.num + &rhs.num) % self.field.prime();
         result
     }
 }
 
 #[impl_bin_ops]
-impl<'a> Sub for FiniteFieldElement<'a> {
-    fn sub(self, rhs : Self) -> FiniteFieldElement<'a> {
+impl Sub for FiniteFieldElement {
+    fn sub(self, rhs : Self) -> FiniteFieldElement {
         let mut result = self.field.gen(&BigInt::zero());
-        result.num = (&self.num + &self.field.prime() - &rhs.num) % &self.field.prime();
+        result.num = (&self.num + self.field.prime() - &rhs.num) % self.field.prime();
         result
     }
 }
 
 #[impl_bin_ops]
-impl<'a> Mul for FiniteFieldElement<'a> {
-    fn mul(self, rhs : Self) -> FiniteFieldElement<'a> {
+impl Mul for FiniteFieldElement {
+    fn mul(self, rhs : Self) -> FiniteFieldElement {
         let mut result = self.field.zero();
-        result.num = (&self.num * &rhs.num) % &self.field.prime();
+        result.num = (&self.num * &rhs.num) % self.field.prime();
         result
     }
 }
 
 /// Scalar multiplication as Z-module
-impl Mul<BigInt> for FiniteFieldElement<'_> {
+/// The bits of |rhs| are added up and the sign is applied at the end, so a * (-n) = -(a * n)
+impl Mul<BigInt> for FiniteFieldElement {
     type Output = Self;
     fn mul(self, rhs : BigInt) -> Self {
         let mut result = self.field.zero();
@@ -256,7 +321,10 @@ impl Mul<BigInt> for FiniteFieldElement<'_> {
             }
         }
 
-        result
+        match rhs.sign() {
+            Sign::Minus => -result,
+            _ => result,
+        }
     }
 }
 
@@ -264,6 +332,7 @@ impl Mul<BigInt> for FiniteFieldElement<'_> {
 #[cfg(test)]
 mod tests{
     use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
     #[test]
     fn field_test() {
         let prime_num = 97;
@@ -281,5 +350,104 @@ mod tests{
             println!("{:?}^2 -> {:?}", d, c);
             assert_eq!(d.clone() * d.clone(), c, "Wrong sqrt"); 
         }
+
+        // the sign of the scalar isn't dropped with its magnitude
+        let a = fp.gen(&BigInt::from(5));
+        assert_eq!(a.clone() * BigInt::from(-3), fp.gen(&BigInt::from(-15)));
+        assert_eq!(a.clone() * BigInt::from(-3) + a * BigInt::from(3), fp.zero());
+    }
+
+    #[test]
+    fn seeded_rng_test() {
+        // p = 1 mod 4 uses Peralta's algorithm
+        let fp = FiniteField::new(&BigUint::from(1000033_u32));
+        let (mut rng1, mut rng2) = (StdRng::seed_from_u64(11), StdRng::seed_from_u64(11));
+
+        for _ in 0..20 {
+            let (a1, a2) = (fp.rand_with_rng(&mut rng1, None), fp.rand_with_rng(&mut rng2, None));
+            assert_eq!(a1, a2);
+
+            let square = &a1 * &a1;
+            let (root1, root2) = (square.sqrt_with_rng(&mut rng1).unwrap(), square.sqrt_with_rng(&mut rng2).unwrap());
+            assert_eq!(root1, root2);
+            assert_eq!(&root1 * &root1, square);
+        }
+    }
+
+    #[test]
+    fn jacobi_test() {
+        // (a / n) is the product of the Legendre symbols given by Euler's criterion
+        let primes = [3_u32, 5, 7, 11, 13];
+        let n : u32 = primes.iter().product();
+        for a in 0..2 * n {
+            let expected : i8 = primes.iter().map(|p| {
+                match BigUint::from(a).modpow(&BigUint::from((p - 1) / 2), &BigUint::from(*p)) {
+                    x if x.is_zero() => 0,
+                    x if x.is_one() => 1,
+                    _ => -1,
+                }
+            }).product();
+            assert_eq!(jacobi(&BigUint::from(a), &BigUint::from(n)), expected, "Wrong Jacobi symbol ({a} / {n})");
+        }
+    }
+
+    /// Compare nth_root against all the n-th powers
+    #[test]
+    fn nth_root_exhaustive_test() {
+        // 97 - 1 = 2^5 * 3 and 101 - 1 = 2^2 * 5^2
+        for prime_num in [97_u32, 101] {
+            let fp = FiniteField::new(&BigUint::from(prime_num));
+            let elements : Vec<FiniteFieldElement> = (0..prime_num).map(|a| fp.gen(&BigInt::from(a))).collect();
+
+            for x in elements.iter() {
+                assert_eq!(x.legendre(), match x.is_zero() {
+                    true => 0,
+                    false => if elements.iter().any(|y| y * y == *x) {1} else {-1},
+                });
+            }
+
+            for n in 1..=12 {
+                let powers : Vec<FiniteFieldElement> = elements.iter().map(|x| x.pow(&BigInt::from(n))).collect();
+                for x in elements.iter() {
+                    match x.nth_root(n) {
+                        Some(root) => assert_eq!(root.pow(&BigInt::from(n)), *x, "Wrong {n}-th root of {x:?} over F_{prime_num}"),
+                        None => assert!(!powers.contains(x), "No {n}-th root of the {n}-th power {x:?} over F_{prime_num}"),
+                    }
+                }
+            }
+        }
+
+        // every element is a cube if p = 2 mod 3
+        let fp = FiniteField::new(&BigUint::from(101_u32));
+        let x = fp.gen(&BigInt::from(7));
+        assert_eq!(x.cube_root().unwrap().pow(&BigInt::from(3)), x);
+    }
+
+    /// The r-th roots for a large prime r dividing p - 1 finish in O(sqrt(r)) steps
+    #[test]
+    fn nth_root_large_prime_test() {
+        // p = 2 * r + 1 with the prime r = 4294967681
+        let r : u64 = 4294967681;
+        let fp = FiniteField::new(&BigUint::from(2 * r + 1));
+        for _ in 0..3 {
+            let a = fp.rand(None).pow(&BigInt::from(r));
+            assert_eq!(a.nth_root(r).unwrap().pow(&BigInt::from(r)), a);
+        }
+        // the r-th powers are 1 and -1
+        assert!(fp.gen(&BigInt::from(2)).nth_root(r).is_none());
+    }
+
+    #[test]
+    fn batch_inv_test() {
+        let fp = FiniteField::new(&BigUint::from(1000003_u32));
+        assert!(batch_inv::<FiniteFieldElement>(&[]).is_empty());
+
+        let elements : Vec<FiniteFieldElement> = (0..50).map(|_| fp.rand(None))
+            .filter(|x| !x.is_zero()).collect();
+        let inverses = batch_inv(&elements);
+        assert_eq!(inverses.len(), elements.len());
+        for (x, x_inv) in elements.iter().zip(inverses.iter()) {
+            assert_eq!(x.inv(), *x_inv);
+        }
     }
 }
\ No newline at end of file
@@ -1,16 +1,21 @@
 //! Implementation of quadratic extension field
-use num::BigUint;
-use num::bigint::ToBigInt;
-use num_prime::buffer::{NaiveBuffer, PrimeBufferExt};
+use core::fmt;
+use super::*;
+use super::hash_to_field::HashToField;
+use std::ops::{Mul, Neg};
+use num::{BigInt, BigUint, Zero, One};
+use num::bigint::{ToBigInt, Sign};
 use super::fp::{FiniteField, FiniteFieldElement};
-use crate::field::FieldElement;
+use impl_ops::impl_bin_ops;
+use std::sync::Arc;
+use rand::RngCore;
 
 /// Type for a base of a quadratic field
 /// Fp[\alpha]
 #[derive(Debug, Clone)]
 pub struct FiniteField2 {
     base_field : FiniteField,
-    alpha : BigUint,
+    alpha : Arc<FiniteFieldElement>,
 }
 
 impl FiniteField2 {
@@ -18,10 +23,468 @@ impl FiniteField2 {
     /// alpha must be non-quadratic residue
     pub fn new(base_field : &FiniteField, alpha : &BigUint) -> Self {
         let fp_alpha = base_field.gen(&alpha.to_bigint().unwrap());
-        assert!(fp_alpha.sqrt() == None, "The alpha is quadratic residue!");
+        assert!(fp_alpha.sqrt().is_none(), "The alpha is quadratic residue!");
         FiniteField2 {
             base_field : base_field.clone(),
-            alpha : alpha.clone(),
+            alpha : Arc::new(fp_alpha),
         }
     }
-}
\ No newline at end of file
+
+    /// Output an initialized [FiniteField2Element](FiniteField2Element)
+    /// (a, b) represents a + b * \sqrt{alpha}
+    pub fn gen(&self, (a, b) : (&BigInt, &BigInt)) -> FiniteField2Element {
+        FiniteField2Element {
+            field : self.clone(),
+            a : self.base_field.gen(a),
+            b : self.base_field.gen(b),
+        }
+    }
+
+    /// Output the base field
+    pub fn base_field(&self) -> &FiniteField {
+        &self.base_field
+    }
+
+    /// Embed an element of the base field
+    pub fn embed(&self, a : &FiniteFieldElement) -> FiniteField2Element {
+        FiniteField2Element {
+            field : self.clone(),
+            a : a.clone(),
+            b : self.base_field.zero(),
+        }
+    }
+
+    /// Output the non-residue alpha as an element of the base field
+    fn alpha(&self) -> FiniteFieldElement {
+        (*self.alpha).clone()
+    }
+}
+
+/// FiniteField2 type is a [Field](Field)
+impl Field for FiniteField2 {
+    type Element = FiniteField2Element;
+    /// Implement DynZero for [FiniteField2](FiniteField2)
+    fn zero(&self) -> Self::Element {
+        self.gen((&BigInt::zero(), &BigInt::zero()))
+    }
+    /// Implement DynOne for [FiniteField2](FiniteField2)
+    fn one(&self) -> Self::Element {
+        self.gen((&BigInt::one(), &BigInt::zero()))
+    }
+
+    /// Generate a random element
+    fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R, size : Option<usize>) -> Self::Element {
+        FiniteField2Element {
+            field : self.clone(),
+            a : self.base_field.rand_with_rng(rng, size),
+            b : self.base_field.rand_with_rng(rng, size),
+        }
+    }
+
+    /// Characteristic
+    fn characteristic(&self) -> BigInt {
+        self.base_field.characteristic()
+    }
+
+    fn extension_degree(&self) -> usize {
+        2
+    }
+}
+
+impl FieldParsing for FiniteField2 {
+    /// Parse a + b*i
+    fn element_from_str(&self, s : &str) -> Result<Self::Element, ParseError> {
+        let (a, b) = sage::parse_quadratic(s)?;
+        Ok(self.gen((&a, &b)))
+    }
+}
+
+impl FieldEncoding for FiniteField2 {
+    fn byte_len(&self) -> usize {
+        2 * self.base_field.byte_len()
+    }
+
+    /// Decode a || b for a + b * i
+    fn element_from_bytes(&self, bytes : &[u8]) -> Result<Self::Element, DecodeError> {
+        encoding::check_len(bytes, self.byte_len())?;
+        let (a, b) = bytes.split_at(self.base_field.byte_len());
+        Ok(FiniteField2Element {
+            field : self.clone(),
+            a : self.base_field.element_from_bytes(a)?,
+            b : self.base_field.element_from_bytes(b)?,
+        })
+    }
+}
+
+impl fmt::Display for FiniteField2 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Finite Field in i of size {}^2", self.base_field.prime())
+    }
+}
+
+/// The coordinates of a + b * \sqrt{alpha} are (a, b)
+impl HashToField for FiniteField2 {
+    fn element_from_coordinates(&self, coordinates : &[BigInt]) -> Self::Element {
+        self.gen((&coordinates[0], &coordinates[1]))
+    }
+}
+
+/// FiniteField2Element type for the elements in [FiniteField2](FiniteField2)
+/// It represents a + b * \sqrt{alpha} with a, b in the base field
+#[derive(Clone)]
+pub struct FiniteField2Element {
+    field : FiniteField2,
+    a : FiniteFieldElement,
+    b : FiniteFieldElement,
+}
+
+impl fmt::Debug for FiniteField2Element {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} + {:?} * i", self.a, self.b)
+    }
+}
+
+/// b*i + a as in Sage
+impl fmt::Display for FiniteField2Element {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        sage::write_sum(f, &[(self.b.to_string(), "i".to_string()), (self.a.to_string(), String::new())])
+    }
+}
+
+impl FiniteField2Element {
+    /// The p-power Frobenius (a + b * i)^p = a - b * i
+    pub fn frobenius(&self) -> Self {
+        self.conjugate()
+    }
+
+    /// The conjugate a - b * i
+    pub fn conjugate(&self) -> Self {
+        FiniteField2Element {
+            field : self.field.clone(),
+            a : self.a.clone(),
+            b : -self.b.clone(),
+        }
+    }
+
+    /// The norm (a + b * i)(a - b * i) = a^2 - alpha * b^2 in the base field
+    pub fn norm(&self) -> FiniteFieldElement {
+        self.a.clone() * self.a.clone() - self.field.alpha() * self.b.clone() * self.b.clone()
+    }
+
+    /// The trace (a + b * i) + (a - b * i) = 2a in the base field
+    pub fn trace(&self) -> FiniteFieldElement {
+        self.a.clone() + self.a.clone()
+    }
+
+    /// Whether it's fixed by the Frobenius or not
+    pub fn is_in_base_field(&self) -> bool {
+        self.b.is_zero()
+    }
+}
+
+impl FieldElement for FiniteField2Element {
+    /// (a + b * i)^{-1} = (a - b * i) / (a^2 - alpha * b^2)
+    fn inv(&self) -> Self {
+        let norm = self.norm();
+        assert!(!norm.is_zero(), "There is no multiplicative inverse of {self:?}");
+        let norm_inv = norm.inv();
+
+        FiniteField2Element {
+            field : self.field.clone(),
+            a : self.a.clone() * norm_inv.clone(),
+            b : -self.b.clone() * norm_inv,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.a.is_zero() && self.b.is_zero()
+    }
+
+    /// Compute the power operation
+    fn pow(&self, exponent : &BigInt) -> Self {
+        let mut result = self.field.one();
+        let mut tmp_value = self.clone();
+
+        for digit in exponent.iter_u32_digits() {
+            for i in 0..32 {
+                if (digit >> i) & 1 == 1 {
+                    result = result.clone() * tmp_value.clone();
+                }
+                tmp_value = tmp_value.clone() * tmp_value.clone();
+            }
+        }
+        match exponent.sign() {
+            Sign::Minus => result.inv(),
+            _ => result,
+        }
+    }
+
+    /// a + b * i is a square iff its norm a^2 - alpha * b^2 is a square in the base field
+    fn is_square(&self) -> bool {
+        self.norm().is_square()
+    }
+
+    /// The quadratic character is the Legendre symbol of the norm
+    fn legendre(&self) -> i8 {
+        self.norm().legendre()
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        self.sqrt_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Compute the square root by reducing to square roots in the base field
+    /// If p = 3 mod 4 and alpha = -1, use [Algorithm 9](https://eprint.iacr.org/2012/685.pdf) instead
+    fn sqrt_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> Option<Self> {
+        let prime = self.field.characteristic();
+        let alpha = self.field.alpha();
+
+        if prime.clone() % 4 == BigInt::from(3) && alpha == -self.field.base_field.one() {
+            let a1 = self.pow(&((prime.clone() - 3) >> 2));
+            let x0 = &a1 * self;
+            let alpha0 = &a1 * &x0;
+            let root = match alpha0 == -self.field.one() {
+                true => self.field.gen((&BigInt::zero(), &BigInt::one())) * x0,
+                false => (alpha0 + self.field.one()).pow(&((prime - 1) >> 1)) * x0,
+            };
+
+            return match &root * &root == *self {
+                true => Some(root),
+                false => None,
+            };
+        }
+
+        let two_inv = self.field.base_field.gen(&BigInt::from(2)).inv();
+
+        // a is in the base field : either \sqrt{a} or \sqrt{a / alpha} * i
+        if self.b.is_zero() {
+            return match self.a.sqrt_with_rng(rng) {
+                Some(c) => Some(FiniteField2Element { field : self.field.clone(), a : c, b : self.field.base_field.zero() }),
+                None => (self.a.clone() * alpha.inv()).sqrt_with_rng(rng)
+                    .map(|d| FiniteField2Element { field : self.field.clone(), a : self.field.base_field.zero(), b : d }),
+            };
+        }
+
+        // (c + d * i)^2 = a + b * i iff c^2 = (a +- \sqrt{N(a + b * i)}) / 2 and d = b / 2c
+        let s = self.norm().sqrt_with_rng(rng)?;
+        let c = match ((self.a.clone() + s.clone()) * two_inv.clone()).sqrt_with_rng(rng) {
+            Some(c) if !c.is_zero() => c,
+            _ => ((self.a.clone() - s) * two_inv.clone()).sqrt_with_rng(rng)?,
+        };
+        let d = self.b.clone() * two_inv * c.inv();
+
+        Some(FiniteField2Element { field : self.field.clone(), a : c, b : d })
+    }
+}
+
+impl ElementEncoding for FiniteField2Element {
+    /// Encode a + b * i as a || b
+    fn to_bytes(&self) -> Vec<u8> {
+        [self.a.to_bytes(), self.b.to_bytes()].concat()
+    }
+}
+
+impl NthRoot for FiniteField2Element {
+    fn nth_root_with_rng<R : RngCore + ?Sized>(&self, n : u64, rng : &mut R) -> Option<Self> {
+        nth_root(&self.field, self, n, rng)
+    }
+}
+
+impl Neg for FiniteField2Element {
+    type Output = Self;
+    fn neg(self) -> Self {
+        FiniteField2Element { field : self.field, a : -self.a, b : -self.b }
+    }
+}
+
+impl PartialEq for FiniteField2Element {
+    fn eq(&self, rhs:&Self) -> bool {
+        self.a == rhs.a && self.b == rhs.b
+    }
+}
+
+#[impl_bin_ops]
+impl Add for FiniteField2Element {
+    fn add(self, rhs : FiniteField2Element) -> FiniteField2Element {
+        FiniteField2Element {
+            field : self.field.clone(),
+            a : &self.a + &rhs.a,
+            b : &self.b + &rhs.b,
+        }
+    }
+}
+
+#[impl_bin_ops]
+impl Sub for FiniteField2Element {
+    fn sub(self, rhs : FiniteField2Element) -> FiniteField2Element {
+        FiniteField2Element {
+            field : self.field.clone(),
+            a : &self.a - &rhs.a,
+            b : &self.b - &rhs.b,
+        }
+    }
+}
+
+/// (a1 + b1 * i)(a2 + b2 * i) = (a1 * a2 + alpha * b1 * b2) + (a1 * b2 + b1 * a2) * i
+#[impl_bin_ops]
+impl Mul for FiniteField2Element {
+    fn mul(self, rhs : FiniteField2Element) -> FiniteField2Element {
+        FiniteField2Element {
+            field : self.field.clone(),
+            a : &self.a * &rhs.a + self.field.alpha() * &self.b * &rhs.b,
+            b : &self.a * &rhs.b + &self.b * &rhs.a,
+        }
+    }
+}
+
+/// Scalar multiplication as Z-module
+impl Mul<BigInt> for FiniteField2Element {
+    type Output = Self;
+    fn mul(self, rhs : BigInt) -> Self {
+        FiniteField2Element {
+            field : self.field.clone(),
+            a : self.a * rhs.clone(),
+            b : self.b * rhs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::elliptic_curve::mont_curve::MontgomeryCurve;
+    use crate::group::GroupElement;
+
+    #[test]
+    fn field2_test() {
+        let prime_num = 103;
+        let fp = FiniteField::new(&BigUint::from(prime_num as u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(prime_num as u32 - 1));
+        let order = BigInt::from(prime_num * prime_num - 1);
+
+        for _ in 0..100 {
+            let mut a = fp2.rand(None);
+            while a.is_zero() {a = fp2.rand(None);}
+            let b = fp2.rand(None);
+            let c = fp2.rand(None);
+
+            assert_eq!(a.pow(&order), fp2.one());
+            assert_eq!(&a * a.inv(), fp2.one());
+            assert_eq!(&a * (&b + &c), &a * &b + &a * &c);
+            assert_eq!(a.clone() * BigInt::from(3), &a + &a + &a);
+
+            let sqr = &a * &a;
+            let d = sqr.sqrt().unwrap();
+            assert_eq!(&d * &d, sqr, "Wrong sqrt");
+        }
+    }
+
+    #[test]
+    fn field2_frobenius_test() {
+        let prime_num = 103;
+        let fp = FiniteField::new(&BigUint::from(prime_num as u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(5_u32));
+
+        for _ in 0..100 {
+            let a = fp2.rand(None);
+            let b = fp2.rand(None);
+
+            assert_eq!(a.frobenius(), a.pow(&BigInt::from(prime_num)));
+            assert_eq!(a.frobenius().frobenius(), a);
+            assert_eq!(fp2.embed(&a.norm()), &a * a.conjugate());
+            assert_eq!(fp2.embed(&a.trace()), &a + a.conjugate());
+            assert_eq!((&a * &b).norm(), a.norm() * b.norm());
+            assert_eq!((&a + &b).trace(), a.trace() + b.trace());
+            assert!(a.norm().is_zero() || (&a * a.frobenius()).is_in_base_field());
+            assert_eq!(a.is_in_base_field(), a.frobenius() == a);
+        }
+
+        assert!(fp2.embed(&fp.rand(None)).is_in_base_field());
+        assert!(!fp2.gen((&BigInt::one(), &BigInt::one())).is_in_base_field());
+    }
+
+    /// Compare sqrt and is_square against the set of all squares for small primes
+    #[test]
+    fn field2_sqrt_exhaustive_test() {
+        for prime_num in [3_u32, 5, 7, 11, 13, 17, 19, 23, 29, 31] {
+            let fp = FiniteField::new(&BigUint::from(prime_num));
+            let minus_one = BigUint::from(prime_num - 1);
+            let mut alphas = vec![(2..prime_num).map(BigUint::from)
+                .find(|alpha| !fp.gen(&alpha.to_bigint().unwrap()).is_square()).unwrap()];
+            if prime_num % 4 == 3 {
+                alphas.push(minus_one);
+            }
+
+            for alpha in alphas {
+                let fp2 = FiniteField2::new(&fp, &alpha);
+                let elements : Vec<FiniteField2Element> = (0..prime_num).flat_map(|a| (0..prime_num)
+                    .map(move |b| (BigInt::from(a), BigInt::from(b))))
+                    .map(|(a, b)| fp2.gen((&a, &b))).collect();
+                let squares : Vec<FiniteField2Element> = elements.iter().map(|x| x * x).collect();
+
+                for x in elements.iter() {
+                    let expected = squares.contains(x);
+                    assert_eq!(x.is_square(), expected, "Wrong residuosity of {x:?} over F_{prime_num}^2");
+                    match x.sqrt() {
+                        Some(root) => assert_eq!(&root * &root, *x, "Wrong sqrt of {x:?} over F_{prime_num}^2"),
+                        None => assert!(!expected, "No sqrt of the square {x:?} over F_{prime_num}^2"),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn field2_nth_root_test() {
+        // 13^2 - 1 = 2^3 * 3 * 7
+        let fp = FiniteField::new(&BigUint::from(13_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(2_u32));
+        let elements : Vec<FiniteField2Element> = (0..13).flat_map(|a| (0..13)
+            .map(move |b| (BigInt::from(a), BigInt::from(b))))
+            .map(|(a, b)| fp2.gen((&a, &b))).collect();
+
+        for n in [2, 3, 4, 7, 8, 12, 14, 15] {
+            let powers : Vec<FiniteField2Element> = elements.iter().map(|x| x.pow(&BigInt::from(n))).collect();
+            for x in elements.iter() {
+                match x.nth_root(n) {
+                    Some(root) => assert_eq!(root.pow(&BigInt::from(n)), *x, "Wrong {n}-th root of {x:?}"),
+                    None => assert!(!powers.contains(x), "No {n}-th root of the {n}-th power {x:?}"),
+                }
+                assert_eq!(x.legendre() >= 0, x.is_square());
+            }
+        }
+    }
+
+    #[test]
+    fn field2_display_test() {
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(430_u32));
+        assert_eq!(fp2.to_string(), "Finite Field in i of size 431^2");
+
+        let cases = [((3, 6), "6*i + 3"), ((0, 1), "i"), ((5, 0), "5"), ((0, 0), "0"), ((1, 430), "430*i + 1")];
+        for ((a, b), expected) in cases {
+            let element = fp2.gen((&BigInt::from(a), &BigInt::from(b)));
+            assert_eq!(element.to_string(), expected);
+            assert_eq!(fp2.element_from_str(expected), Ok(element));
+        }
+
+        for _ in 0..100 {
+            let element = fp2.rand(None);
+            assert_eq!(fp2.element_from_str(&element.to_string()), Ok(element));
+        }
+        assert_eq!(fp2.element_from_str("-i + 2*i - 1"), Ok(fp2.gen((&BigInt::from(-1), &BigInt::from(1)))));
+        assert_eq!(fp2.element_from_str("3*j"), Err(ParseError::InvalidNumber));
+        assert_eq!(fp2.element_from_str("i^2"), Err(ParseError::InvalidSyntax));
+    }
+
+    #[test]
+    fn montgomery_curve_over_field2_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(102_u32));
+        let curve = MontgomeryCurve::new(&fp2, fp2.zero());
+
+        let point = curve.rand();
+        assert!((point * BigInt::from(104)).is_zero());
+        assert!(curve.is_supersingular(), "The curve is ordinary");
+    }
+}
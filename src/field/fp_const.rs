@@ -0,0 +1,732 @@
+//! Implementation of prime fields and their quadratic extensions fixed at compile time
+//!
+//! A prime field is defined by a type implementing [PrimeFieldParams](PrimeFieldParams),
+//! usually through the [prime_field](crate::prime_field) macro.
+//! Every constant is precomputed at compile time and the elements don't carry any pointer to their field.
+use core::fmt;
+use super::*;
+use super::hash_to_field::HashToField;
+use super::limbs;
+use std::marker::PhantomData;
+use std::ops::{Mul, Neg};
+use num::{BigInt, BigUint};
+use num::bigint::{ToBigInt, Sign, RandomBits};
+use impl_ops::impl_bin_ops;
+use rand::{Rng, RngCore};
+use subtle::Choice;
+
+/// Constants of a prime field p < 2^{64N}
+/// Only MODULUS must be given, the others are computed at compile time
+pub trait PrimeFieldParams<const N: usize> : 'static + Clone + Copy + fmt::Debug + Send + Sync {
+    /// The odd prime p as little-endian limbs
+    const MODULUS : [u64; N];
+    /// -p^{-1} mod 2^64
+    const P_INV : u64 = limbs::neg_inv(Self::MODULUS[0]);
+    /// R mod p where R = 2^{64N}
+    const R : [u64; N] = limbs::pow2_mod(64 * N, &Self::MODULUS);
+    /// R^2 mod p
+    const R2 : [u64; N] = limbs::pow2_mod(128 * N, &Self::MODULUS);
+    /// p - 2 for the inversion
+    const INV_EXP : [u64; N] = limbs::sub_small(&Self::MODULUS, 2);
+    /// (p - 1) / 2 for Euler's criterion
+    const EULER_EXP : [u64; N] = limbs::shr(&limbs::sub_small(&Self::MODULUS, 1), 1);
+    /// (p + 1) / 4 for the square root when p = 3 mod 4
+    const SQRT_EXP : [u64; N] = limbs::shr(&limbs::add_small(&Self::MODULUS, 1), 2);
+    /// A quadratic non-residue in Montgomery form, -1 if p = 3 mod 4
+    /// The quadratic extension is Fp[i] with i^2 = NON_RESIDUE
+    const NON_RESIDUE : [u64; N] = limbs::non_residue(&Self::MODULUS, Self::P_INV, &Self::R, &Self::R2);
+}
+
+/// Parse the modulus of the [prime_field](crate::prime_field) macro
+#[doc(hidden)]
+pub const fn modulus_from_hex<const N: usize>(hex : &str) -> [u64; N] {
+    limbs::from_hex(hex)
+}
+
+/// Define a type implementing [PrimeFieldParams](PrimeFieldParams) from a hexadecimal prime
+///
+/// `prime_field!(pub P103, 1, "0x67");` makes `ConstPrimeField<P103, 1>` and `ConstField2<P103, 1>` fields
+/// The prime is not checked at compile time
+#[macro_export]
+macro_rules! prime_field {
+    ($(#[$meta:meta])* $vis:vis $name:ident, $limbs:expr, $modulus:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        $vis struct $name;
+
+        impl $crate::field::fp_const::PrimeFieldParams<$limbs> for $name {
+            const MODULUS : [u64; $limbs] = $crate::field::fp_const::modulus_from_hex($modulus);
+        }
+    };
+}
+
+/// Type for a prime field given by [PrimeFieldParams](PrimeFieldParams)
+/// It has no runtime data at all
+#[derive(Debug, Clone, Copy)]
+pub struct ConstPrimeField<P : PrimeFieldParams<N>, const N: usize> {
+    params : PhantomData<P>,
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> ConstPrimeField<P, N> {
+    pub fn new() -> Self {
+        ConstPrimeField { params : PhantomData }
+    }
+
+    /// Output an initialized [ConstPrimeFieldElement](ConstPrimeFieldElement)
+    pub fn gen(&self, num : &BigInt) -> ConstPrimeFieldElement<P, N> {
+        let prime = self.prime().to_bigint().unwrap();
+        let mut tmp = num % &prime;
+        if tmp.sign() == Sign::Minus {
+            tmp += prime;
+        }
+
+        let limbs = limbs::from_biguint(&tmp.to_biguint().unwrap());
+        ConstPrimeFieldElement::from_mont(limbs::mont_mul(&limbs, &P::R2, &P::MODULUS, P::P_INV))
+    }
+
+    /// Output its prime
+    pub fn prime(&self) -> BigUint {
+        limbs::to_biguint(&P::MODULUS)
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> Default for ConstPrimeField<P, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ConstPrimeField type is a [Field](Field)
+impl<P : PrimeFieldParams<N>, const N: usize> Field for ConstPrimeField<P, N> {
+    type Element = ConstPrimeFieldElement<P, N>;
+    /// Implement DynZero for [ConstPrimeField](ConstPrimeField)
+    fn zero(&self) -> Self::Element {
+        ConstPrimeFieldElement::from_mont([0u64; N])
+    }
+    /// Implement DynOne for [ConstPrimeField](ConstPrimeField)
+    fn one(&self) -> Self::Element {
+        ConstPrimeFieldElement::from_mont(P::R)
+    }
+
+    /// Generate a random element
+    fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R, size : Option<usize>) -> Self::Element {
+        let num : BigInt = match size {
+            Some(size) => {
+                rng.sample(RandomBits::new(size.try_into().unwrap()))
+            },
+            None => {
+                rng.sample(RandomBits::new(self.prime().bits()))
+            }
+        };
+
+        self.gen(&num)
+    }
+
+    /// Characteristic
+    fn characteristic(&self) -> BigInt {
+        self.prime().to_bigint().unwrap()
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> FieldParsing for ConstPrimeField<P, N> {
+    fn element_from_str(&self, s : &str) -> Result<Self::Element, ParseError> {
+        Ok(self.gen(&sage::parse_integer(s)?))
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> FieldEncoding for ConstPrimeField<P, N> {
+    fn byte_len(&self) -> usize {
+        encoding::modulus_len(&self.prime())
+    }
+
+    fn element_from_bytes(&self, bytes : &[u8]) -> Result<Self::Element, DecodeError> {
+        let num = encoding::uint_from_bytes(bytes, &self.prime())?;
+        Ok(self.gen(&num.to_bigint().unwrap()))
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> fmt::Display for ConstPrimeField<P, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Finite Field of size {}", self.prime())
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> HashToField for ConstPrimeField<P, N> {
+    fn element_from_coordinates(&self, coordinates : &[BigInt]) -> Self::Element {
+        self.gen(&coordinates[0])
+    }
+}
+
+/// ConstPrimeFieldElement type for the elements in [ConstPrimeField](ConstPrimeField)
+/// The limbs hold a * R mod p for the represented element a
+#[derive(Clone, Copy)]
+pub struct ConstPrimeFieldElement<P : PrimeFieldParams<N>, const N: usize> {
+    limbs : [u64; N],
+    params : PhantomData<P>,
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> ConstPrimeFieldElement<P, N> {
+    fn from_mont(limbs : [u64; N]) -> Self {
+        ConstPrimeFieldElement { limbs, params : PhantomData }
+    }
+
+    fn pow_limbs(&self, exponent : &[u64; N]) -> Self {
+        Self::from_mont(limbs::mont_pow(&self.limbs, exponent, &P::R, &P::MODULUS, P::P_INV))
+    }
+
+    /// Output the represented integer in [0, p)
+    pub fn to_biguint(&self) -> BigUint {
+        let mut one = [0u64; N];
+        one[0] = 1;
+        limbs::to_biguint(&limbs::mont_mul(&self.limbs, &one, &P::MODULUS, P::P_INV))
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> fmt::Debug for ConstPrimeFieldElement<P, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.to_biguint())
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> fmt::Display for ConstPrimeFieldElement<P, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_biguint())
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> FieldElement for ConstPrimeFieldElement<P, N> {
+    /// Fermat's little theorem : a^{-1} = a^{p-2}
+    fn inv(&self) -> Self {
+        assert!(!self.is_zero(), "There is no multiplicative inverse of {self:?}");
+        self.pow_limbs(&P::INV_EXP)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|limb| *limb == 0)
+    }
+
+    /// Compute the power operation
+    fn pow(&self, exponent : &BigInt) -> Self {
+        let mut result = Self::from_mont(P::R);
+        let mut tmp_value = *self;
+
+        for digit in exponent.iter_u64_digits() {
+            for i in 0..64 {
+                if (digit >> i) & 1 == 1 {
+                    result = result * tmp_value;
+                }
+                tmp_value = tmp_value * tmp_value;
+            }
+        }
+        match exponent.sign() {
+            Sign::Minus => result.inv(),
+            _ => result,
+        }
+    }
+
+    /// Euler's criterion : a^{(p-1)/2} = 1
+    fn is_square(&self) -> bool {
+        self.is_zero() || self.pow_limbs(&P::EULER_EXP).limbs == P::R
+    }
+
+    /// The Legendre symbol (a / p) computed by the binary Jacobi symbol algorithm
+    fn legendre(&self) -> i8 {
+        jacobi(&self.to_biguint(), &limbs::to_biguint(&P::MODULUS))
+    }
+
+    /// Compute the square root using a^{(p+1)/4} if p = 3 mod 4 or Tonelli-Shanks algorithm otherwise
+    fn sqrt(&self) -> Option<Self> {
+        if P::MODULUS[0] & 3 == 3 {
+            let root = self.pow_limbs(&P::SQRT_EXP);
+            return match root * root == *self {
+                true => Some(root),
+                false => None,
+            };
+        }
+
+        let prime = ConstPrimeField::<P, N>::new().characteristic();
+        tonelli_shanks(self, &Self::from_mont(P::NON_RESIDUE), &prime)
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> ElementEncoding for ConstPrimeFieldElement<P, N> {
+    fn to_bytes(&self) -> Vec<u8> {
+        encoding::uint_to_bytes(&self.to_biguint(), ConstPrimeField::<P, N>::new().byte_len())
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> NthRoot for ConstPrimeFieldElement<P, N> {
+    fn nth_root_with_rng<R : RngCore + ?Sized>(&self, n : u64, rng : &mut R) -> Option<Self> {
+        nth_root(&ConstPrimeField::<P, N>::new(), self, n, rng)
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> ConditionallySelectable for ConstPrimeFieldElement<P, N> {
+    fn conditional_select(a : &Self, b : &Self, choice : Choice) -> Self {
+        Self::from_mont(limbs::select(&a.limbs, &b.limbs, limbs::choice_mask(choice)))
+    }
+
+    fn ct_eq(&self, rhs : &Self) -> Choice {
+        limbs::ct_eq(&self.limbs, &rhs.limbs)
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> Neg for ConstPrimeFieldElement<P, N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::from_mont(limbs::sub_mod(&[0u64; N], &self.limbs, &P::MODULUS))
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> PartialEq for ConstPrimeFieldElement<P, N> {
+    fn eq(&self, rhs:&Self) -> bool {
+        self.limbs == rhs.limbs
+    }
+}
+
+#[impl_bin_ops]
+impl<P : PrimeFieldParams<N>, const N: usize> Add for ConstPrimeFieldElement<P, N> {
+    fn add(self, rhs : ConstPrimeFieldElement<P, N>) -> ConstPrimeFieldElement<P, N> {
+        ConstPrimeFieldElement::from_mont(limbs::add_mod(&self.limbs, &rhs.limbs, &P::MODULUS))
+    }
+}
+
+#[impl_bin_ops]
+impl<P : PrimeFieldParams<N>, const N: usize> Sub for ConstPrimeFieldElement<P, N> {
+    fn sub(self, rhs : ConstPrimeFieldElement<P, N>) -> ConstPrimeFieldElement<P, N> {
+        ConstPrimeFieldElement::from_mont(limbs::sub_mod(&self.limbs, &rhs.limbs, &P::MODULUS))
+    }
+}
+
+#[impl_bin_ops]
+impl<P : PrimeFieldParams<N>, const N: usize> Mul for ConstPrimeFieldElement<P, N> {
+    fn mul(self, rhs : ConstPrimeFieldElement<P, N>) -> ConstPrimeFieldElement<P, N> {
+        ConstPrimeFieldElement::from_mont(limbs::mont_mul(&self.limbs, &rhs.limbs, &P::MODULUS, P::P_INV))
+    }
+}
+
+/// Scalar multiplication as Z-module
+impl<P : PrimeFieldParams<N>, const N: usize> Mul<BigInt> for ConstPrimeFieldElement<P, N> {
+    type Output = Self;
+    fn mul(self, rhs : BigInt) -> Self {
+        self * ConstPrimeField::<P, N>::new().gen(&rhs)
+    }
+}
+
+/// Type for the quadratic extension Fp[i] with i^2 = [NON_RESIDUE](PrimeFieldParams::NON_RESIDUE)
+/// It has no runtime data at all
+#[derive(Debug, Clone, Copy)]
+pub struct ConstField2<P : PrimeFieldParams<N>, const N: usize> {
+    params : PhantomData<P>,
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> ConstField2<P, N> {
+    pub fn new() -> Self {
+        ConstField2 { params : PhantomData }
+    }
+
+    /// Output the base field
+    pub fn base_field(&self) -> ConstPrimeField<P, N> {
+        ConstPrimeField::new()
+    }
+
+    /// Output an initialized [ConstField2Element](ConstField2Element)
+    /// (a, b) represents a + b * i
+    pub fn gen(&self, (a, b) : (&BigInt, &BigInt)) -> ConstField2Element<P, N> {
+        ConstField2Element { a : self.base_field().gen(a), b : self.base_field().gen(b) }
+    }
+
+    /// Embed an element of the base field
+    pub fn embed(&self, a : &ConstPrimeFieldElement<P, N>) -> ConstField2Element<P, N> {
+        ConstField2Element { a : *a, b : self.base_field().zero() }
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> Default for ConstField2<P, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ConstField2 type is a [Field](Field)
+impl<P : PrimeFieldParams<N>, const N: usize> Field for ConstField2<P, N> {
+    type Element = ConstField2Element<P, N>;
+    /// Implement DynZero for [ConstField2](ConstField2)
+    fn zero(&self) -> Self::Element {
+        self.embed(&self.base_field().zero())
+    }
+    /// Implement DynOne for [ConstField2](ConstField2)
+    fn one(&self) -> Self::Element {
+        self.embed(&self.base_field().one())
+    }
+
+    /// Generate a random element
+    fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R, size : Option<usize>) -> Self::Element {
+        ConstField2Element { a : self.base_field().rand_with_rng(rng, size), b : self.base_field().rand_with_rng(rng, size) }
+    }
+
+    /// Characteristic
+    fn characteristic(&self) -> BigInt {
+        self.base_field().characteristic()
+    }
+
+    fn extension_degree(&self) -> usize {
+        2
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> FieldParsing for ConstField2<P, N> {
+    /// Parse a + b*i
+    fn element_from_str(&self, s : &str) -> Result<Self::Element, ParseError> {
+        let (a, b) = sage::parse_quadratic(s)?;
+        Ok(self.gen((&a, &b)))
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> FieldEncoding for ConstField2<P, N> {
+    fn byte_len(&self) -> usize {
+        2 * self.base_field().byte_len()
+    }
+
+    /// Decode a || b for a + b * i
+    fn element_from_bytes(&self, bytes : &[u8]) -> Result<Self::Element, DecodeError> {
+        encoding::check_len(bytes, self.byte_len())?;
+        let (a, b) = bytes.split_at(self.base_field().byte_len());
+        Ok(ConstField2Element { a : self.base_field().element_from_bytes(a)?, b : self.base_field().element_from_bytes(b)? })
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> fmt::Display for ConstField2<P, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Finite Field in i of size {}^2", self.base_field().prime())
+    }
+}
+
+/// The coordinates of a + b * i are (a, b)
+impl<P : PrimeFieldParams<N>, const N: usize> HashToField for ConstField2<P, N> {
+    fn element_from_coordinates(&self, coordinates : &[BigInt]) -> Self::Element {
+        self.gen((&coordinates[0], &coordinates[1]))
+    }
+}
+
+/// ConstField2Element type for the elements in [ConstField2](ConstField2)
+/// It represents a + b * i with a, b in the base field
+#[derive(Clone, Copy)]
+pub struct ConstField2Element<P : PrimeFieldParams<N>, const N: usize> {
+    a : ConstPrimeFieldElement<P, N>,
+    b : ConstPrimeFieldElement<P, N>,
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> fmt::Debug for ConstField2Element<P, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} + {:?} * i", self.a, self.b)
+    }
+}
+
+/// b*i + a as in Sage
+impl<P : PrimeFieldParams<N>, const N: usize> fmt::Display for ConstField2Element<P, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        sage::write_sum(f, &[(self.b.to_string(), "i".to_string()), (self.a.to_string(), String::new())])
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> ConstField2Element<P, N> {
+    fn non_residue() -> ConstPrimeFieldElement<P, N> {
+        ConstPrimeFieldElement::from_mont(P::NON_RESIDUE)
+    }
+
+    /// The p-power Frobenius (a + b * i)^p = a - b * i
+    pub fn frobenius(&self) -> Self {
+        self.conjugate()
+    }
+
+    /// The conjugate a - b * i
+    pub fn conjugate(&self) -> Self {
+        ConstField2Element { a : self.a, b : -self.b }
+    }
+
+    /// The norm (a + b * i)(a - b * i) = a^2 - i^2 * b^2 in the base field
+    pub fn norm(&self) -> ConstPrimeFieldElement<P, N> {
+        self.a * self.a - Self::non_residue() * self.b * self.b
+    }
+
+    /// The trace (a + b * i) + (a - b * i) = 2a in the base field
+    pub fn trace(&self) -> ConstPrimeFieldElement<P, N> {
+        self.a + self.a
+    }
+
+    /// Whether it's fixed by the Frobenius or not
+    pub fn is_in_base_field(&self) -> bool {
+        self.b.is_zero()
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> FieldElement for ConstField2Element<P, N> {
+    /// (a + b * i)^{-1} = (a - b * i) / (a^2 - i^2 * b^2)
+    fn inv(&self) -> Self {
+        let norm = self.norm();
+        assert!(!norm.is_zero(), "There is no multiplicative inverse of {self:?}");
+        let norm_inv = norm.inv();
+
+        ConstField2Element { a : self.a * norm_inv, b : -self.b * norm_inv }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.a.is_zero() && self.b.is_zero()
+    }
+
+    /// Compute the power operation
+    fn pow(&self, exponent : &BigInt) -> Self {
+        let field = ConstField2::<P, N>::new();
+        let mut result = field.one();
+        let mut tmp_value = *self;
+
+        for digit in exponent.iter_u64_digits() {
+            for i in 0..64 {
+                if (digit >> i) & 1 == 1 {
+                    result = result * tmp_value;
+                }
+                tmp_value = tmp_value * tmp_value;
+            }
+        }
+        match exponent.sign() {
+            Sign::Minus => result.inv(),
+            _ => result,
+        }
+    }
+
+    /// a + b * i is a square iff its norm is a square in the base field
+    fn is_square(&self) -> bool {
+        self.norm().is_square()
+    }
+
+    /// The quadratic character is the Legendre symbol of the norm
+    fn legendre(&self) -> i8 {
+        self.norm().legendre()
+    }
+
+    /// Compute the square root by reducing to square roots in the base field
+    /// If p = 3 mod 4 then i^2 = -1 and [Algorithm 9](https://eprint.iacr.org/2012/685.pdf) is used instead
+    fn sqrt(&self) -> Option<Self> {
+        let field = ConstField2::<P, N>::new();
+        let base_field = field.base_field();
+        let prime = field.characteristic();
+
+        if P::MODULUS[0] & 3 == 3 {
+            let a1 = self.pow(&((prime.clone() - 3) >> 2));
+            let x0 = a1 * self;
+            let alpha0 = a1 * x0;
+            let root = match alpha0 == -field.one() {
+                true => ConstField2Element { a : base_field.zero(), b : base_field.one() } * x0,
+                false => (alpha0 + field.one()).pow(&((prime - 1) >> 1)) * x0,
+            };
+
+            return match root * root == *self {
+                true => Some(root),
+                false => None,
+            };
+        }
+
+        let two_inv = base_field.gen(&BigInt::from(2)).inv();
+
+        // a is in the base field : either \sqrt{a} or \sqrt{a / i^2} * i
+        if self.b.is_zero() {
+            return match self.a.sqrt() {
+                Some(c) => Some(field.embed(&c)),
+                None => (self.a * Self::non_residue().inv()).sqrt()
+                    .map(|d| ConstField2Element { a : base_field.zero(), b : d }),
+            };
+        }
+
+        // (c + d * i)^2 = a + b * i iff c^2 = (a +- \sqrt{N(a + b * i)}) / 2 and d = b / 2c
+        let s = self.norm().sqrt()?;
+        let c = match ((self.a + s) * two_inv).sqrt() {
+            Some(c) if !c.is_zero() => c,
+            _ => ((self.a - s) * two_inv).sqrt()?,
+        };
+
+        Some(ConstField2Element { a : c, b : self.b * two_inv * c.inv() })
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> ElementEncoding for ConstField2Element<P, N> {
+    /// Encode a + b * i as a || b
+    fn to_bytes(&self) -> Vec<u8> {
+        [self.a.to_bytes(), self.b.to_bytes()].concat()
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> NthRoot for ConstField2Element<P, N> {
+    fn nth_root_with_rng<R : RngCore + ?Sized>(&self, n : u64, rng : &mut R) -> Option<Self> {
+        nth_root(&ConstField2::<P, N>::new(), self, n, rng)
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> ConditionallySelectable for ConstField2Element<P, N> {
+    fn conditional_select(a : &Self, b : &Self, choice : Choice) -> Self {
+        ConstField2Element {
+            a : ConstPrimeFieldElement::conditional_select(&a.a, &b.a, choice),
+            b : ConstPrimeFieldElement::conditional_select(&a.b, &b.b, choice),
+        }
+    }
+
+    fn ct_eq(&self, rhs : &Self) -> Choice {
+        self.a.ct_eq(&rhs.a) & self.b.ct_eq(&rhs.b)
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> Neg for ConstField2Element<P, N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        ConstField2Element { a : -self.a, b : -self.b }
+    }
+}
+
+impl<P : PrimeFieldParams<N>, const N: usize> PartialEq for ConstField2Element<P, N> {
+    fn eq(&self, rhs:&Self) -> bool {
+        self.a == rhs.a && self.b == rhs.b
+    }
+}
+
+#[impl_bin_ops]
+impl<P : PrimeFieldParams<N>, const N: usize> Add for ConstField2Element<P, N> {
+    fn add(self, rhs : ConstField2Element<P, N>) -> ConstField2Element<P, N> {
+        ConstField2Element { a : self.a + rhs.a, b : self.b + rhs.b }
+    }
+}
+
+#[impl_bin_ops]
+impl<P : PrimeFieldParams<N>, const N: usize> Sub for ConstField2Element<P, N> {
+    fn sub(self, rhs : ConstField2Element<P, N>) -> ConstField2Element<P, N> {
+        ConstField2Element { a : self.a - rhs.a, b : self.b - rhs.b }
+    }
+}
+
+/// (a1 + b1 * i)(a2 + b2 * i) = (a1 * a2 + i^2 * b1 * b2) + (a1 * b2 + b1 * a2) * i
+#[impl_bin_ops]
+impl<P : PrimeFieldParams<N>, const N: usize> Mul for ConstField2Element<P, N> {
+    fn mul(self, rhs : ConstField2Element<P, N>) -> ConstField2Element<P, N> {
+        ConstField2Element {
+            a : self.a * rhs.a + ConstField2Element::non_residue() * self.b * rhs.b,
+            b : self.a * rhs.b + self.b * rhs.a,
+        }
+    }
+}
+
+/// Scalar multiplication as Z-module
+impl<P : PrimeFieldParams<N>, const N: usize> Mul<BigInt> for ConstField2Element<P, N> {
+    type Output = Self;
+    fn mul(self, rhs : BigInt) -> Self {
+        let scalar = ConstPrimeField::<P, N>::new().gen(&rhs);
+        ConstField2Element { a : self.a * scalar, b : self.b * scalar }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::field::fp::FiniteField;
+    use crate::field::fp2::{FiniteField2, FiniteField2Element};
+    use crate::field::params::{Fp434, Fp434Sqr, Fp751, FpCsidh512};
+    use crate::elliptic_curve::mont_curve::MontgomeryCurve;
+    use crate::group::GroupElement;
+    use crate::field::counting::{self, Counting};
+    use num::One;
+
+    prime_field!(P103, 1, "0x67");
+    prime_field!(P97, 1, "0x61");
+
+    /// Compare every operation with the [BigUint](num::BigUint) backend
+    fn differential_test<P : PrimeFieldParams<N>, const N: usize>() {
+        let fp_const = ConstPrimeField::<P, N>::new();
+        let fp = FiniteField::new(&fp_const.prime());
+        let non_residue = ConstPrimeFieldElement::<P, N>::from_mont(P::NON_RESIDUE).to_biguint();
+        let fp2 = FiniteField2::new(&fp, &non_residue);
+        let to_fp = |x : &ConstPrimeFieldElement<P, N>| fp.gen(&x.to_biguint().to_bigint().unwrap());
+        let to_fp2 = |x : &ConstField2Element<P, N>| -> FiniteField2Element {
+            fp2.gen((&x.a.to_biguint().to_bigint().unwrap(), &x.b.to_biguint().to_bigint().unwrap()))
+        };
+
+        let mut elements = vec![fp_const.zero(), fp_const.one(), -fp_const.one()];
+        elements.extend((0..30).map(|_| fp_const.rand(None)));
+        for a in elements.iter() {
+            for b in elements.iter() {
+                assert_eq!(to_fp(&(a + b)), to_fp(a) + to_fp(b));
+                assert_eq!(to_fp(&(a - b)), to_fp(a) - to_fp(b));
+                assert_eq!(to_fp(&(a * b)), to_fp(a) * to_fp(b));
+            }
+
+            assert_eq!(a.is_square(), to_fp(a).is_square());
+            if !a.is_zero() {
+                assert_eq!(to_fp(&a.inv()), to_fp(a).inv());
+            }
+            match a.sqrt() {
+                Some(root) => assert_eq!(root * root, *a),
+                None => assert!(!to_fp(a).is_square()),
+            }
+        }
+
+        let fp2_const = ConstField2::<P, N>::new();
+        for _ in 0..30 {
+            let (a, b) = (fp2_const.rand(None), fp2_const.rand(None));
+            assert_eq!(to_fp2(&(a * b)), to_fp2(&a) * to_fp2(&b));
+            assert_eq!(to_fp2(&a.frobenius()), to_fp2(&a).frobenius());
+            assert_eq!(a.is_square(), to_fp2(&a).is_square());
+            if !a.is_zero() {
+                assert_eq!(to_fp2(&a.inv()), to_fp2(&a).inv());
+            }
+
+            let sqr = a * a;
+            let root = sqr.sqrt().unwrap();
+            assert_eq!(root * root, sqr);
+        }
+    }
+
+    #[test]
+    fn const_field_test() {
+        differential_test::<P103, 1>();
+        differential_test::<P97, 1>();
+        differential_test::<crate::field::params::P434, 7>();
+    }
+
+    #[test]
+    fn pow_ct_test() {
+        let fp = Fp434::new();
+        let base = fp.rand(None);
+        let mut random_exponent = [0u64; 7];
+        random_exponent.iter_mut().for_each(|limb| *limb = rand::random());
+        let exponents = [[0u64; 7], [1, 0, 0, 0, 0, 0, 0], [u64::MAX; 7], random_exponent];
+
+        let mut counts = vec![];
+        for exponent in exponents.iter() {
+            let expected = base.pow(&limbs::to_biguint(exponent).to_bigint().unwrap());
+            assert_eq!(base.pow_ct(exponent, 448), expected);
+
+            counting::reset();
+            let result = Counting(base).pow_ct(exponent, 448);
+            counts.push(counting::count());
+            assert_eq!(result.0, expected);
+        }
+
+        assert!(counts.iter().all(|count| *count == counts[0]), "The operations depend on the exponent : {counts:?}");
+    }
+
+    #[test]
+    fn params_test() {
+        let three = BigUint::from(3_u32);
+        assert_eq!(Fp434::new().prime(), (BigUint::one() << 216) * three.pow(137) - BigUint::one());
+        assert_eq!(Fp751::new().prime(), (BigUint::one() << 372) * three.pow(239) - BigUint::one());
+
+        let small_primes = (3_u32..374).filter(|n| (2..*n).all(|d| n % d != 0)).chain([587]);
+        assert_eq!(FpCsidh512::new().prime(), small_primes.map(BigUint::from).product::<BigUint>() * 4_u32 - BigUint::one());
+    }
+
+    #[test]
+    fn const_field_curve_test() {
+        // The SIKE starting curve y^2 = x^3 + 6x^2 + x has (p+1)^2 points over F_{p^2}
+        let fp2 = Fp434Sqr::new();
+        let curve = MontgomeryCurve::new(&fp2, fp2.gen((&BigInt::from(6), &BigInt::zero())));
+        let point = curve.rand();
+        assert!((point * (fp2.characteristic() + 1)).is_zero());
+    }
+}
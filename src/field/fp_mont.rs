@@ -0,0 +1,367 @@
+//! Implementation of prime fields with fixed-width limbs and Montgomery multiplication
+use core::fmt;
+use super::*;
+use super::hash_to_field::HashToField;
+use super::limbs;
+use std::ops::{Mul, Neg};
+use num::{BigInt, BigUint, One, Integer};
+use num::bigint::{ToBigInt, Sign, RandomBits};
+use num_prime::buffer::NaiveBuffer;
+use num_prime::buffer::PrimeBufferExt;
+use impl_ops::impl_bin_ops;
+use rand::{Rng, RngCore};
+use std::sync::Arc;
+use subtle::Choice;
+
+/// Type for a prime field whose elements are stored as N limbs of u64 in Montgomery form
+/// The prime must be less than 2^{64N}
+#[derive(Debug, Clone)]
+pub struct MontgomeryField<const N: usize> {
+    params : Arc<MontgomeryFieldParams<N>>,
+}
+
+/// Precomputed constants shared by all handles of a [MontgomeryField](MontgomeryField)
+#[derive(Debug)]
+struct MontgomeryFieldParams<const N: usize> {
+    prime : BigUint,
+    modulus : [u64; N],
+    /// -p^{-1} mod 2^64
+    p_inv : u64,
+    /// R mod p where R = 2^{64N}
+    r : [u64; N],
+    /// R^2 mod p
+    r2 : [u64; N],
+    /// A quadratic non-residue in Montgomery form
+    non_residue : [u64; N],
+}
+
+impl<const N: usize> MontgomeryField<N> {
+    /// The input [BigUint](num::BigUint) must be an odd prime less than 2^{64N} or it will panic
+    pub fn new(prime : &BigUint) -> Self {
+        let pb = NaiveBuffer::new();
+        assert!(pb.is_prime(prime, None).probably(), "The base number is not prime!");
+        assert!(prime.is_odd(), "The base number must be odd!");
+        assert!(prime.bits() <= 64 * N as u64, "The base number doesn't fit in {N} limbs!");
+
+        let modulus = limbs::from_biguint(prime);
+        let r = limbs::from_biguint(&((BigUint::one() << (64 * N)) % prime));
+        let r2 = limbs::from_biguint(&((BigUint::one() << (128 * N)) % prime));
+        let p_inv = limbs::neg_inv(modulus[0]);
+
+        // the least quadratic non-residue by Euler's criterion
+        let check_exp = (prime - BigUint::one()) >> 1;
+        let mut non_residue = BigUint::from(2_u32);
+        while non_residue.modpow(&check_exp, prime).is_one() {
+            non_residue += BigUint::one();
+        }
+
+        let params = MontgomeryFieldParams {
+            prime : prime.clone(),
+            modulus,
+            p_inv,
+            r,
+            r2,
+            non_residue : limbs::mont_mul(&limbs::from_biguint(&non_residue), &r2, &modulus, p_inv),
+        };
+
+        MontgomeryField { params : Arc::new(params) }
+    }
+
+    /// Output an initialized [MontgomeryFieldElement](MontgomeryFieldElement)
+    pub fn gen(&self, num : &BigInt) -> MontgomeryFieldElement<N> {
+        let mut tmp = num % self.params.prime.to_bigint().unwrap();
+        if tmp.sign() == Sign::Minus {
+            tmp += self.params.prime.to_bigint().unwrap();
+        }
+
+        let limbs = limbs::from_biguint(&tmp.to_biguint().unwrap());
+        self.element(self.mont_mul(&limbs, &self.params.r2))
+    }
+
+    /// Output its prime
+    pub fn prime(&self) -> &BigUint {
+        &self.params.prime
+    }
+
+    fn element(&self, limbs : [u64; N]) -> MontgomeryFieldElement<N> {
+        MontgomeryFieldElement { field : self.clone(), limbs }
+    }
+
+    fn mont_mul(&self, a : &[u64; N], b : &[u64; N]) -> [u64; N] {
+        limbs::mont_mul(a, b, &self.params.modulus, self.params.p_inv)
+    }
+}
+
+/// MontgomeryField type is a [Field](Field)
+impl<const N: usize> Field for MontgomeryField<N> {
+    type Element = MontgomeryFieldElement<N>;
+    /// Implement DynZero for [MontgomeryField](MontgomeryField)
+    fn zero(&self) -> Self::Element {
+        self.element([0u64; N])
+    }
+    /// Implement DynOne for [MontgomeryField](MontgomeryField)
+    fn one(&self) -> Self::Element {
+        self.element(self.params.r)
+    }
+
+    /// Generate a random element
+    fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R, size : Option<usize>) -> Self::Element {
+        let num : BigInt = match size {
+            Some(size) => {
+                rng.sample(RandomBits::new(size.try_into().unwrap()))
+            },
+            None => {
+                rng.sample(RandomBits::new(self.params.prime.bits()))
+            }
+        };
+
+        self.gen(&num)
+    }
+
+    /// Characteristic
+    fn characteristic(&self) -> BigInt {
+        self.params.prime.to_bigint().unwrap()
+    }
+}
+
+impl<const N: usize> FieldParsing for MontgomeryField<N> {
+    fn element_from_str(&self, s : &str) -> Result<Self::Element, ParseError> {
+        Ok(self.gen(&sage::parse_integer(s)?))
+    }
+}
+
+impl<const N: usize> FieldEncoding for MontgomeryField<N> {
+    fn byte_len(&self) -> usize {
+        encoding::modulus_len(self.prime())
+    }
+
+    fn element_from_bytes(&self, bytes : &[u8]) -> Result<Self::Element, DecodeError> {
+        let num = encoding::uint_from_bytes(bytes, self.prime())?;
+        Ok(self.gen(&num.to_bigint().unwrap()))
+    }
+}
+
+impl<const N: usize> fmt::Display for MontgomeryField<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Finite Field of size {}", self.prime())
+    }
+}
+
+impl<const N: usize> HashToField for MontgomeryField<N> {
+    fn element_from_coordinates(&self, coordinates : &[BigInt]) -> Self::Element {
+        self.gen(&coordinates[0])
+    }
+}
+
+/// MontgomeryFieldElement type for the elements in [MontgomeryField](MontgomeryField)
+/// The limbs hold a * R mod p for the represented element a
+#[derive(Clone)]
+pub struct MontgomeryFieldElement<const N: usize> {
+    field : MontgomeryField<N>,
+    limbs : [u64; N],
+}
+
+impl<const N: usize> MontgomeryFieldElement<N> {
+    /// Output the represented integer in [0, p)
+    pub fn to_biguint(&self) -> BigUint {
+        let mut one = [0u64; N];
+        one[0] = 1;
+        limbs::to_biguint(&self.field.mont_mul(&self.limbs, &one))
+    }
+}
+
+impl<const N: usize> fmt::Debug for MontgomeryFieldElement<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.to_biguint())
+    }
+}
+
+impl<const N: usize> fmt::Display for MontgomeryFieldElement<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_biguint())
+    }
+}
+
+impl<const N: usize> FieldElement for MontgomeryFieldElement<N> {
+    /// Fermat's little theorem : a^{-1} = a^{p-2}
+    fn inv(&self) -> Self {
+        assert!(!self.is_zero(), "There is no multiplicative inverse of {self:?}");
+        self.pow(&(self.field.characteristic() - 2))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|limb| *limb == 0)
+    }
+
+    /// Compute the power operation
+    fn pow(&self, exponent : &BigInt) -> Self {
+        let mut result = self.field.one();
+        let mut tmp_value = self.clone();
+
+        for digit in exponent.iter_u64_digits() {
+            for i in 0..64 {
+                if (digit >> i) & 1 == 1 {
+                    result = &result * &tmp_value;
+                }
+                tmp_value = &tmp_value * &tmp_value;
+            }
+        }
+        match exponent.sign() {
+            Sign::Minus => result.inv(),
+            _ => result,
+        }
+    }
+
+    fn is_square(&self) -> bool {
+        self.legendre() >= 0
+    }
+
+    /// The Legendre symbol (a / p) computed by the binary Jacobi symbol algorithm
+    fn legendre(&self) -> i8 {
+        jacobi(&self.to_biguint(), self.field.prime())
+    }
+
+    /// Compute the square root using a^{(p+1)/4} if p = 3 mod 4 or Tonelli-Shanks algorithm otherwise
+    fn sqrt(&self) -> Option<Self> {
+        let prime = self.field.characteristic();
+
+        if prime.clone() % 4 == BigInt::from(3) {
+            return match self.is_square() {
+                true => Some(self.pow(&((prime + 1) >> 2))),
+                false => None,
+            };
+        }
+
+        tonelli_shanks(self, &self.field.element(self.field.params.non_residue), &prime)
+    }
+}
+
+impl<const N: usize> ElementEncoding for MontgomeryFieldElement<N> {
+    fn to_bytes(&self) -> Vec<u8> {
+        encoding::uint_to_bytes(&self.to_biguint(), self.field.byte_len())
+    }
+}
+
+impl<const N: usize> NthRoot for MontgomeryFieldElement<N> {
+    fn nth_root_with_rng<R : RngCore + ?Sized>(&self, n : u64, rng : &mut R) -> Option<Self> {
+        nth_root(&self.field, self, n, rng)
+    }
+}
+
+impl<const N: usize> ConditionallySelectable for MontgomeryFieldElement<N> {
+    fn conditional_select(a : &Self, b : &Self, choice : Choice) -> Self {
+        a.field.element(limbs::select(&a.limbs, &b.limbs, limbs::choice_mask(choice)))
+    }
+
+    fn ct_eq(&self, rhs : &Self) -> Choice {
+        limbs::ct_eq(&self.limbs, &rhs.limbs)
+    }
+}
+
+impl<const N: usize> Neg for MontgomeryFieldElement<N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.field.zero() - self
+    }
+}
+
+impl<const N: usize> PartialEq for MontgomeryFieldElement<N> {
+    fn eq(&self, rhs:&Self) -> bool {
+        assert!(Arc::ptr_eq(&self.field.params, &rhs.field.params) || self.field.params.modulus == rhs.field.params.modulus, "The base field is not equal");
+        self.limbs == rhs.limbs
+    }
+}
+
+#[impl_bin_ops]
+impl<const N: usize> Add for MontgomeryFieldElement<N> {
+    fn add(self, rhs : MontgomeryFieldElement<N>) -> MontgomeryFieldElement<N> {
+        self.field.element(limbs::add_mod(&self.limbs, &rhs.limbs, &self.field.params.modulus))
+    }
+}
+
+#[impl_bin_ops]
+impl<const N: usize> Sub for MontgomeryFieldElement<N> {
+    fn sub(self, rhs : MontgomeryFieldElement<N>) -> MontgomeryFieldElement<N> {
+        self.field.element(limbs::sub_mod(&self.limbs, &rhs.limbs, &self.field.params.modulus))
+    }
+}
+
+#[impl_bin_ops]
+impl<const N: usize> Mul for MontgomeryFieldElement<N> {
+    fn mul(self, rhs : MontgomeryFieldElement<N>) -> MontgomeryFieldElement<N> {
+        self.field.element(self.field.mont_mul(&self.limbs, &rhs.limbs))
+    }
+}
+
+/// Scalar multiplication as Z-module
+impl<const N: usize> Mul<BigInt> for MontgomeryFieldElement<N> {
+    type Output = Self;
+    fn mul(self, rhs : BigInt) -> Self {
+        let scalar = self.field.gen(&rhs);
+        self * scalar
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::field::fp::FiniteField;
+    use crate::elliptic_curve::mont_curve::MontgomeryCurve;
+    use crate::group::GroupElement;
+    use crate::poly::Polynomial;
+
+    /// Compare every operation with the [BigUint](num::BigUint) backend
+    fn differential_test<const N: usize>(prime : &BigUint) {
+        let fp = FiniteField::new(prime);
+        let fp_mont = MontgomeryField::<N>::new(prime);
+        let to_fp = |x : &MontgomeryFieldElement<N>| fp.gen(&x.to_biguint().to_bigint().unwrap());
+
+        let mut edge_cases = vec![fp_mont.zero(), fp_mont.one(), -fp_mont.one(), fp_mont.gen(&BigInt::from(2))];
+        edge_cases.extend((0..50).map(|_| fp_mont.rand(None)));
+
+        for a in edge_cases.iter() {
+            for b in edge_cases.iter() {
+                let (a_fp, b_fp) = (to_fp(a), to_fp(b));
+                assert_eq!(to_fp(&(a + b)), &a_fp + &b_fp);
+                assert_eq!(to_fp(&(a - b)), &a_fp - &b_fp);
+                assert_eq!(to_fp(&(a * b)), &a_fp * &b_fp);
+            }
+
+            let a_fp = to_fp(a);
+            assert_eq!(to_fp(&-a.clone()), -a_fp.clone());
+            assert_eq!(to_fp(&(a.clone() * BigInt::from(-12345))), a_fp.clone() * BigInt::from(-12345));
+            assert_eq!(a.is_square(), a_fp.is_square());
+            if !a.is_zero() {
+                assert_eq!(to_fp(&a.inv()), a_fp.inv());
+            }
+            match a.sqrt() {
+                Some(root) => assert_eq!(&root * &root, *a),
+                None => assert!(a_fp.sqrt().is_none()),
+            }
+        }
+    }
+
+    #[test]
+    fn montgomery_field_test() {
+        differential_test::<1>(&BigUint::from(97_u32));
+        differential_test::<1>(&BigUint::from(u64::MAX - 58));
+        differential_test::<2>(&((BigUint::one() << 127) - BigUint::one()));
+        differential_test::<4>(&((BigUint::one() << 255) - BigUint::from(19_u32)));
+    }
+
+    #[test]
+    fn montgomery_field_p434_test() {
+        // SIKE p434 = 2^216 * 3^137 - 1
+        let prime = (BigUint::one() << 216) * BigUint::from(3_u32).pow(137) - BigUint::one();
+        differential_test::<7>(&prime);
+
+        let fp = MontgomeryField::<7>::new(&prime);
+        let curve = MontgomeryCurve::new(&fp, fp.zero());
+        let point = curve.rand();
+        assert!((point * (fp.characteristic() + 1)).is_zero());
+
+        let poly = Polynomial::new(&fp, vec![fp.rand(None), fp.rand(None), fp.one()]);
+        let square = poly.clone() * poly.clone();
+        assert!((square % poly).is_zero());
+    }
+}
@@ -0,0 +1,151 @@
+//! Hashing to finite fields following RFC 9380
+//!
+//! The messages are expanded with expand_message_xmd and every coordinate over the prime field
+//! is reduced from L = ceil((ceil(log2(p)) + k) / 8) bytes with the security parameter k = 128.
+//! The domain separation tag must be unique to the protocol and the field it hashes to.
+use super::{FieldEncoding, ElementEncoding};
+use num::{BigInt, BigUint, Zero};
+use num::bigint::Sign;
+use sha2::digest::{Digest, core_api::BlockSizeUser};
+
+/// The target security level in bits
+pub const SECURITY_BITS : usize = 128;
+
+/// Fields whose elements are built from coordinates over the prime field
+pub trait HashToField : FieldEncoding {
+    /// The element e_0 + e_1 * i + ... + e_{m-1} * i^{m-1} from the integers e_j modulo p
+    fn element_from_coordinates(&self, coordinates : &[BigInt]) -> Self::Element;
+
+    /// The coordinates over the prime field, read from the canonical encoding
+    fn coordinates(&self, element : &Self::Element) -> Vec<BigUint> {
+        let bytes = element.to_bytes();
+        bytes.chunks(bytes.len() / self.extension_degree()).map(BigUint::from_bytes_le).collect()
+    }
+}
+
+/// expand_message_xmd of RFC 9380 with a hash function H of Merkle-Damgard type
+/// A tag longer than 255 bytes is replaced with H("H2C-OVERSIZE-DST-" || dst)
+pub fn expand_message_xmd<H : Digest + BlockSizeUser>(msg : &[u8], dst : &[u8], len : usize) -> Vec<u8> {
+    let b_len = <H as Digest>::output_size();
+    let ell = len.div_ceil(b_len);
+    assert!(ell <= 255 && len <= 65535, "The output of expand_message_xmd is too long");
+
+    let dst = match dst.len() > 255 {
+        true => H::new().chain_update(b"H2C-OVERSIZE-DST-").chain_update(dst).finalize().to_vec(),
+        false => dst.to_vec(),
+    };
+    let dst_prime = [dst.as_slice(), &[dst.len() as u8]].concat();
+
+    // b_0 = H(Z_pad || msg || l_i_b_str || 0 || DST_prime)
+    let b_0 = H::new()
+        .chain_update(vec![0; H::block_size()])
+        .chain_update(msg)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    // b_i = H(strxor(b_0, b_{i-1}) || i || DST_prime)
+    let mut b_i = H::new().chain_update(&b_0).chain_update([1]).chain_update(&dst_prime).finalize();
+    let mut uniform_bytes = b_i.to_vec();
+    for i in 2..=ell {
+        let xor : Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(x, y)| x ^ y).collect();
+        b_i = H::new().chain_update(xor).chain_update([i as u8]).chain_update(&dst_prime).finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+
+    uniform_bytes.truncate(len);
+    uniform_bytes
+}
+
+/// hash_to_field of RFC 9380 : count elements from the message with expand_message_xmd
+pub fn hash_to_field<F : HashToField, H : Digest + BlockSizeUser>(field : &F, msg : &[u8], dst : &[u8], count : usize) -> Vec<F::Element> {
+    let p = field.characteristic();
+    let m = field.extension_degree();
+    let len = (p.bits() as usize + SECURITY_BITS).div_ceil(8);
+    let uniform_bytes = expand_message_xmd::<H>(msg, dst, count * m * len);
+
+    uniform_bytes.chunks(m * len).map(|chunk| {
+        let coordinates : Vec<BigInt> = chunk.chunks(len)
+            .map(|tv| BigInt::from_bytes_be(Sign::Plus, tv) % &p)
+            .collect();
+        field.element_from_coordinates(&coordinates)
+    }).collect()
+}
+
+/// The sign of an element, sgn0 of RFC 9380
+/// It's the parity of the first nonzero coordinate
+pub fn sgn0<F : HashToField>(field : &F, element : &F::Element) -> bool {
+    field.coordinates(element).iter()
+        .find(|x| !x.is_zero())
+        .is_some_and(|x| x.bit(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_message_xmd, hash_to_field, sgn0, HashToField};
+    use crate::field::{Field, fp::FiniteField, fp_mont::MontgomeryField};
+    use crate::field::params::Fp434Sqr;
+    use crate::encoding;
+    use num::{BigInt, BigUint, Num};
+    use sha2::{Sha256, Sha512};
+
+    #[test]
+    fn expand_message_xmd_test() {
+        // RFC 9380, appendix K.1
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let vectors : [(&[u8], usize, &str); 4] = [
+            (b"", 0x20, "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+            (b"abc", 0x20, "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+            (b"abcdef0123456789", 0x20, "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1"),
+            (b"abc", 0x80, "abba86a6129e366fc877aab32fc4ffc70120d8996c88aee2fe4b32d6c7b6437a\
+                647e6c3163d40b76a73cf6a5674ef1d890f95b664ee0afa5359a5c4e07985635\
+                bbecbac65d747d3d2da7ec2b8221b17b0ca9dc8a1ac1c07ea6a1e60583e2cb00\
+                058e77b7b72a298425cd1b941ad4ec65e8afc50303a22c0f99b0509b4c895f40"),
+        ];
+        for (msg, len, expected) in vectors {
+            assert_eq!(encoding::encode_hex(&expand_message_xmd::<Sha256>(msg, dst, len)), expected);
+        }
+    }
+
+    #[test]
+    fn hash_to_field_test() {
+        // RFC 9380, appendix J.7.1 : curve25519_XMD:SHA-512_ELL2_RO_
+        let prime = (BigUint::from(1_u32) << 255) - BigUint::from(19_u32);
+        let fp = FiniteField::new(&prime);
+        let fp_mont = MontgomeryField::<4>::new(&prime);
+        let dst = b"QUUX-V01-CS02-with-curve25519_XMD:SHA-512_ELL2_RO_";
+        let vectors : [(&[u8], [&str; 2]); 2] = [
+            (b"", ["005fe8a7b8fef0a16c105e6cadf5a6740b3365e18692a9c05bfbb4d97f645a6a",
+                   "1347edbec6a2b5d8c02e058819819bee177077c9d10a4ce165aab0fd0252261a"]),
+            (b"abc", ["49bed021c7a3748f09fa8cdfcac044089f7829d3531066ac9e74e0994e05bc7d",
+                      "5c36525b663e63389d886105cee7ed712325d5a97e60e140aba7e2ce5ae851b6"]),
+        ];
+        for (msg, expected) in vectors {
+            let expected : Vec<BigInt> = expected.iter().map(|u| BigInt::from_str_radix(u, 16).unwrap()).collect();
+            let u = hash_to_field::<_, Sha512>(&fp, msg, dst, 2);
+            assert_eq!(u, vec![fp.gen(&expected[0]), fp.gen(&expected[1])]);
+            let u = hash_to_field::<_, Sha512>(&fp_mont, msg, dst, 2);
+            assert_eq!(u, vec![fp_mont.gen(&expected[0]), fp_mont.gen(&expected[1])]);
+        }
+    }
+
+    #[test]
+    fn field2_hash_test() {
+        let fp2 = Fp434Sqr::new();
+        let u = hash_to_field::<_, Sha256>(&fp2, b"abc", b"DST-A", 2);
+        assert_eq!(u, hash_to_field::<_, Sha256>(&fp2, b"abc", b"DST-A", 2));
+        assert_ne!(u, hash_to_field::<_, Sha256>(&fp2, b"abc", b"DST-B", 2));
+        assert_ne!(u[0], u[1]);
+
+        // the coordinates round trip and the sign follows the first nonzero coordinate
+        let coordinates : Vec<BigInt> = fp2.coordinates(&u[0]).into_iter().map(BigInt::from).collect();
+        assert_eq!(fp2.element_from_coordinates(&coordinates), u[0]);
+        let b = BigInt::from(6);
+        assert!(!sgn0(&fp2, &fp2.element_from_coordinates(&[BigInt::from(0), b.clone()])));
+        assert!(sgn0(&fp2, &fp2.element_from_coordinates(&[BigInt::from(0), -b.clone()])));
+        assert!(sgn0(&fp2, &fp2.element_from_coordinates(&[BigInt::from(1), b])));
+        assert!(!sgn0(&fp2, &fp2.zero()));
+        assert!(!sgn0(&fp2, &-fp2.one()));
+    }
+}
@@ -0,0 +1,274 @@
+//! Fixed-width multiprecision arithmetic on little-endian arrays of u64 limbs
+use num::BigUint;
+use subtle::{Choice, ConstantTimeEq};
+
+/// Convert a [BigUint](num::BigUint) into N limbs
+/// The number must fit in N limbs
+pub(crate) fn from_biguint<const N: usize>(num : &BigUint) -> [u64; N] {
+    let digits = num.to_u64_digits();
+    assert!(digits.len() <= N, "The number doesn't fit in {N} limbs");
+
+    let mut limbs = [0u64; N];
+    limbs[..digits.len()].copy_from_slice(&digits);
+    limbs
+}
+
+/// Convert N limbs into a [BigUint](num::BigUint)
+pub(crate) fn to_biguint<const N: usize>(limbs : &[u64; N]) -> BigUint {
+    let digits : Vec<u32> = limbs.iter().flat_map(|limb| [*limb as u32, (*limb >> 32) as u32]).collect();
+    BigUint::new(digits)
+}
+
+/// a + b + carry
+#[inline(always)]
+const fn adc(a : u64, b : u64, carry : u64) -> (u64, u64) {
+    let tmp = a as u128 + b as u128 + carry as u128;
+    (tmp as u64, (tmp >> 64) as u64)
+}
+
+/// a - b - borrow
+#[inline(always)]
+const fn sbb(a : u64, b : u64, borrow : u64) -> (u64, u64) {
+    let tmp = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (tmp as u64, (tmp >> 127) as u64)
+}
+
+/// a + b * c + carry
+#[inline(always)]
+const fn mac(a : u64, b : u64, c : u64, carry : u64) -> (u64, u64) {
+    let tmp = a as u128 + (b as u128) * (c as u128) + carry as u128;
+    (tmp as u64, (tmp >> 64) as u64)
+}
+
+// The functions below are const fn so that compile-time fields can precompute their constants
+// Thus they use while loops instead of iterators
+// The modular operations never branch on the values, the reductions are done with masks
+
+/// All-ones mask if flag = 1, zero mask if flag = 0
+#[inline(always)]
+const fn mask(flag : u64) -> u64 {
+    0u64.wrapping_sub(flag)
+}
+
+/// All-ones mask if choice = 1, zero mask if choice = 0
+pub(crate) fn choice_mask(choice : Choice) -> u64 {
+    mask(choice.unwrap_u8() as u64)
+}
+
+/// Choice 1 iff a = b, comparing every limb
+pub(crate) fn ct_eq<const N: usize>(a : &[u64; N], b : &[u64; N]) -> Choice {
+    a[..].ct_eq(&b[..])
+}
+
+/// Output a if mask = 0 or b if mask = !0
+pub(crate) const fn select<const N: usize>(a : &[u64; N], b : &[u64; N], mask : u64) -> [u64; N] {
+    let mut result = [0u64; N];
+    let mut i = 0;
+    while i < N {
+        result[i] = a[i] ^ (mask & (a[i] ^ b[i]));
+        i += 1;
+    }
+    result
+}
+
+/// Output (a + b mod 2^{64N}, carry)
+pub(crate) const fn add<const N: usize>(a : &[u64; N], b : &[u64; N]) -> ([u64; N], u64) {
+    let mut result = [0u64; N];
+    let mut carry = 0;
+    let mut i = 0;
+    while i < N {
+        (result[i], carry) = adc(a[i], b[i], carry);
+        i += 1;
+    }
+    (result, carry)
+}
+
+/// Output (a - b mod 2^{64N}, borrow)
+pub(crate) const fn sub<const N: usize>(a : &[u64; N], b : &[u64; N]) -> ([u64; N], u64) {
+    let mut result = [0u64; N];
+    let mut borrow = 0;
+    let mut i = 0;
+    while i < N {
+        (result[i], borrow) = sbb(a[i], b[i], borrow);
+        i += 1;
+    }
+    (result, borrow)
+}
+
+/// a + b mod p for a, b < p
+pub(crate) const fn add_mod<const N: usize>(a : &[u64; N], b : &[u64; N], p : &[u64; N]) -> [u64; N] {
+    let (sum, carry) = add(a, b);
+    let (diff, borrow) = sub(&sum, p);
+    select(&diff, &sum, mask(borrow & !carry & 1))
+}
+
+/// a - b mod p for a, b < p
+pub(crate) const fn sub_mod<const N: usize>(a : &[u64; N], b : &[u64; N], p : &[u64; N]) -> [u64; N] {
+    let (diff, borrow) = sub(a, b);
+    let p_masked = select(&[0u64; N], p, mask(borrow));
+    add(&diff, &p_masked).0
+}
+
+/// Montgomery multiplication a * b * R^{-1} mod p (R = 2^{64N}) for a, b < p
+/// It uses the CIOS method and p_inv = -p^{-1} mod 2^64
+pub(crate) const fn mont_mul<const N: usize>(a : &[u64; N], b : &[u64; N], p : &[u64; N], p_inv : u64) -> [u64; N] {
+    let mut t = [0u64; N];
+    let (mut t_n, mut t_n1) : (u64, u64);
+    t_n = 0;
+
+    let mut i = 0;
+    while i < N {
+        // t = t + a * b[i]
+        let mut carry = 0;
+        let mut j = 0;
+        while j < N {
+            (t[j], carry) = mac(t[j], a[j], b[i], carry);
+            j += 1;
+        }
+        (t_n, t_n1) = adc(t_n, carry, 0);
+
+        // t = (t + m * p) / 2^64
+        let m = t[0].wrapping_mul(p_inv);
+        let (_, mut carry) = mac(t[0], m, p[0], 0);
+        let mut j = 1;
+        while j < N {
+            (t[j - 1], carry) = mac(t[j], m, p[j], carry);
+            j += 1;
+        }
+        (t[N - 1], carry) = adc(t_n, carry, 0);
+        t_n = t_n1 + carry;
+        i += 1;
+    }
+
+    let (diff, borrow) = sub(&t, p);
+    select(&diff, &t, mask(borrow & !t_n & 1))
+}
+
+/// Compute -p^{-1} mod 2^64 for odd p using Newton's iteration
+pub(crate) const fn neg_inv(p0 : u64) -> u64 {
+    let mut inv : u64 = 1;
+    let mut i = 0;
+    while i < 6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(p0.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
+
+/// 2^k mod p for p < 2^{64N}, computed by repeated doubling
+pub(crate) const fn pow2_mod<const N: usize>(k : usize, p : &[u64; N]) -> [u64; N] {
+    let mut result = [0u64; N];
+    result[0] = 1;
+    // 1 mod p
+    let (diff, borrow) = sub(&result, p);
+    if borrow == 0 {
+        result = diff;
+    }
+
+    let mut i = 0;
+    while i < k {
+        result = add_mod(&result, &result, p);
+        i += 1;
+    }
+    result
+}
+
+/// a + n for a small integer n, ignoring the carry
+pub(crate) const fn add_small<const N: usize>(a : &[u64; N], n : u64) -> [u64; N] {
+    let mut b = [0u64; N];
+    b[0] = n;
+    add(a, &b).0
+}
+
+/// a - n for a small integer n, ignoring the borrow
+pub(crate) const fn sub_small<const N: usize>(a : &[u64; N], n : u64) -> [u64; N] {
+    let mut b = [0u64; N];
+    b[0] = n;
+    sub(a, &b).0
+}
+
+/// a >> k for k < 64
+pub(crate) const fn shr<const N: usize>(a : &[u64; N], k : u32) -> [u64; N] {
+    let mut result = [0u64; N];
+    let mut i = 0;
+    while i < N {
+        result[i] = a[i] >> k;
+        if k > 0 && i + 1 < N {
+            result[i] |= a[i + 1] << (64 - k);
+        }
+        i += 1;
+    }
+    result
+}
+
+/// Montgomery exponentiation a^e where a is in Montgomery form and one = R mod p
+/// It always runs 64N squarings and multiplications whatever e is
+pub(crate) const fn mont_pow<const N: usize>(a : &[u64; N], e : &[u64; N], one : &[u64; N], p : &[u64; N], p_inv : u64) -> [u64; N] {
+    let mut result = *one;
+    let mut i = 64 * N;
+    while i > 0 {
+        i -= 1;
+        result = mont_mul(&result, &result, p, p_inv);
+        let product = mont_mul(&result, a, p, p_inv);
+        result = select(&result, &product, mask((e[i / 64] >> (i % 64)) & 1));
+    }
+    result
+}
+
+/// a == b
+pub(crate) const fn eq<const N: usize>(a : &[u64; N], b : &[u64; N]) -> bool {
+    let mut i = 0;
+    while i < N {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The least quadratic non-residue modulo the odd prime p in Montgomery form
+/// It's -1 if p = 3 mod 4, otherwise it's found by Euler's criterion
+pub(crate) const fn non_residue<const N: usize>(p : &[u64; N], p_inv : u64, r : &[u64; N], r2 : &[u64; N]) -> [u64; N] {
+    if p[0] & 3 == 3 {
+        return sub_mod(&[0u64; N], r, p);
+    }
+
+    let euler_exp = shr(&sub_small(p, 1), 1);
+    let mut candidate = [0u64; N];
+    candidate[0] = 2;
+    loop {
+        let candidate_mont = mont_mul(&candidate, r2, p, p_inv);
+        if !eq(&mont_pow(&candidate_mont, &euler_exp, r, p, p_inv), r) {
+            return candidate_mont;
+        }
+        candidate[0] += 1;
+    }
+}
+
+/// Parse a hexadecimal string (with an optional 0x prefix) into N limbs
+pub(crate) const fn from_hex<const N: usize>(hex : &str) -> [u64; N] {
+    let bytes = hex.as_bytes();
+    let mut start = 0;
+    if bytes.len() >= 2 && bytes[0] == b'0' && (bytes[1] == b'x' || bytes[1] == b'X') {
+        start = 2;
+    }
+
+    let mut result = [0u64; N];
+    let mut i = bytes.len();
+    let mut k = 0;
+    while i > start {
+        i -= 1;
+        let digit = match bytes[i] {
+            b'0'..=b'9' => bytes[i] - b'0',
+            b'a'..=b'f' => bytes[i] - b'a' + 10,
+            b'A'..=b'F' => bytes[i] - b'A' + 10,
+            b'_' => continue,
+            _ => panic!("Invalid hexadecimal digit"),
+        };
+        assert!(k < 16 * N, "The number doesn't fit in the limbs");
+        result[k / 16] |= (digit as u64) << (4 * (k % 16));
+        k += 1;
+    }
+    result
+}
@@ -1,29 +1,59 @@
 pub mod fp;
 pub mod fp2;
+pub mod fp_mont;
+pub mod fp_const;
+pub mod extension;
+pub mod hash_to_field;
+pub mod params;
+mod limbs;
+#[cfg(test)]
+pub(crate) mod counting;
 use std::ops::{Add, Sub, Mul, Neg};
 use std::fmt;
-use num::BigInt;
+use std::collections::HashMap;
+use num::{BigInt, BigUint, Zero, One, Integer, integer::Roots};
+use subtle::Choice;
+use rand::RngCore;
+use crate::encoding::{self, DecodeError};
+use crate::sage::{self, ParseError};
 
 /// Base trait for Field types
-pub trait Field<'a> : Clone + fmt::Debug {
-    type Element : FieldElement + 'a;
+/// The fields of the crate display the description of Sage, e.g. `Finite Field in i of size 431^2`
+pub trait Field : Clone + fmt::Debug {
+    type Element : FieldElement;
     /// Trait for the additive identity of a dynamic field type
     /// Output a field element
-    fn zero(&'a self) -> Self::Element;
+    fn zero(&self) -> Self::Element;
     /// Trait for the multiplicative identity of a dynamic field type
     /// Output a field element
-    fn one(&'a self) -> Self::Element;
+    fn one(&self) -> Self::Element;
 
-    /// Generate a random field element
+    /// Generate a random field element using the thread-local random number generator
     /// self.rand(Some(size)) or self.rand(None)
-    fn rand(&'a self, size : Option<usize>) -> Self::Element;
+    fn rand(&self, size : Option<usize>) -> Self::Element {
+        self.rand_with_rng(&mut rand::thread_rng(), size)
+    }
+
+    /// Generate a random field element using the given random number generator
+    fn rand_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R, size : Option<usize>) -> Self::Element;
     
     /// Characteristic of the field
     fn characteristic(&self) -> BigInt;
+
+    /// The degree over the prime field
+    fn extension_degree(&self) -> usize {
+        1
+    }
+
+    /// Number of the elements of the field
+    fn order(&self) -> BigInt {
+        self.characteristic().pow(self.extension_degree() as u32)
+    }
 }
 
-/// FieldElement must refer a Field
-/// Thus it must take as input the lifetime of the Field
+/// FieldElement owns a handle of its Field
+/// Thus it can be moved and stored independently of the Field value it came from
+/// The elements of the fields of the crate display the Sage syntax, e.g. `6*i + 3`
 pub trait FieldElement : Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> +
 Mul<BigInt, Output=Self> + Neg<Output=Self> + 
 Sized + PartialEq + Clone + fmt::Debug {
@@ -35,4 +65,309 @@ Sized + PartialEq + Clone + fmt::Debug {
     fn pow(&self, exponent : &BigInt) -> Self;
     /// Compute the square root
     fn sqrt(&self) -> Option<Self>;
-}
\ No newline at end of file
+    /// Compute the square root, the probabilistic algorithms use the given random number generator
+    fn sqrt_with_rng<R : RngCore + ?Sized>(&self, _rng : &mut R) -> Option<Self> {
+        self.sqrt()
+    }
+    /// Whether it's a square or not
+    /// Implementations should avoid computing the square root itself
+    fn is_square(&self) -> bool {
+        self.sqrt().is_some()
+    }
+    /// The quadratic character : 0 for zero, 1 for nonzero squares and -1 otherwise
+    fn legendre(&self) -> i8 {
+        match (self.is_zero(), self.is_square()) {
+            (true, _) => 0,
+            (false, true) => 1,
+            (false, false) => -1,
+        }
+    }
+}
+
+/// Fields which parse their elements in the format of their Display, see [sage](crate::sage)
+/// Only the fields of the crate are known to display the Sage syntax which the parser reads
+pub trait FieldParsing : Field {
+    /// Parse an element in the format of its Display
+    fn element_from_str(&self, s : &str) -> Result<Self::Element, ParseError>;
+}
+
+/// Fields with the canonical encoding of their elements, see [encoding](crate::encoding)
+/// The encoding needs a fixed byte length for the elements, which not every field has
+pub trait FieldEncoding : Field<Element : ElementEncoding> {
+    /// Length of the canonical encoding of the elements in bytes
+    fn byte_len(&self) -> usize;
+
+    /// Decode the canonical encoding of an element
+    /// It rejects inputs of the wrong length and non-reduced integers
+    fn element_from_bytes(&self, bytes : &[u8]) -> Result<Self::Element, DecodeError>;
+
+    /// Decode the hexadecimal string of the canonical encoding of an element
+    fn element_from_hex(&self, hex : &str) -> Result<Self::Element, DecodeError> {
+        self.element_from_bytes(&encoding::decode_hex(hex)?)
+    }
+}
+
+/// Field elements with the canonical encoding
+pub trait ElementEncoding : FieldElement {
+    /// The canonical fixed-length encoding, see [encoding](crate::encoding)
+    fn to_bytes(&self) -> Vec<u8>;
+    /// The hexadecimal string of the canonical encoding
+    fn to_hex(&self) -> String {
+        encoding::encode_hex(&self.to_bytes())
+    }
+}
+
+/// Field elements with n-th roots
+/// The curve arithmetic only needs square roots, so the other roots are optional
+pub trait NthRoot : FieldElement {
+    /// Compute an n-th root for n > 0
+    fn nth_root(&self, n : u64) -> Option<Self> {
+        self.nth_root_with_rng(n, &mut rand::thread_rng())
+    }
+    /// Compute an n-th root for n > 0 using the given random number generator
+    fn nth_root_with_rng<R : RngCore + ?Sized>(&self, n : u64, rng : &mut R) -> Option<Self>;
+    /// Compute a cube root
+    fn cube_root(&self) -> Option<Self> {
+        self.nth_root(3)
+    }
+}
+
+/// Field elements which can be selected without branching
+/// The constant-time algorithms on secret data are built on it
+pub trait ConditionallySelectable : FieldElement {
+    /// Output a if choice = 0 or b if choice = 1
+    fn conditional_select(a : &Self, b : &Self, choice : Choice) -> Self;
+
+    /// Choice 1 iff self = rhs, without branching on the values
+    fn ct_eq(&self, rhs : &Self) -> Choice;
+
+    /// Swap a and b if choice = 1
+    fn conditional_swap(a : &mut Self, b : &mut Self, choice : Choice) {
+        let tmp = Self::conditional_select(a, b, choice);
+        *b = Self::conditional_select(b, a, choice);
+        *a = tmp;
+    }
+
+    /// Compute the power operation with Montgomery's ladder on the lowest bits of a little-endian exponent
+    /// It always runs the same operations for a fixed number of bits, whatever the exponent is
+    fn pow_ct(&self, exponent : &[u64], bits : usize) -> Self {
+        let mut r0 = self.pow(&BigInt::zero());
+        let mut r1 = self.clone();
+
+        for i in (0..bits).rev() {
+            let limb = exponent.get(i / 64).copied().unwrap_or(0);
+            let bit = Choice::from(((limb >> (i % 64)) & 1) as u8);
+            Self::conditional_swap(&mut r0, &mut r1, bit);
+            r1 = r0.clone() * r1;
+            r0 = r0.clone() * r0;
+            Self::conditional_swap(&mut r0, &mut r1, bit);
+        }
+
+        r0
+    }
+}
+
+/// Invert all the elements with a single inversion using Montgomery's trick
+/// It costs one inversion and 3(n-1) multiplications
+/// Every element must be nonzero or it will panic
+pub fn batch_inv<T : FieldElement>(elements : &[T]) -> Vec<T> {
+    if elements.is_empty() {
+        return vec![];
+    }
+
+    // prefix[i] = elements[0] * ... * elements[i]
+    let mut prefix : Vec<T> = Vec::with_capacity(elements.len());
+    prefix.push(elements[0].clone());
+    for element in elements[1..].iter() {
+        prefix.push(prefix.last().unwrap().clone() * element.clone());
+    }
+
+    // tmp = (elements[0] * ... * elements[i])^{-1}
+    let mut tmp = prefix.last().unwrap().inv();
+    let mut result = prefix;
+    for i in (1..elements.len()).rev() {
+        let element_inv = tmp.clone() * result[i - 1].clone();
+        tmp = tmp * elements[i].clone();
+        result[i] = element_inv;
+    }
+    result[0] = tmp;
+
+    result
+}
+
+/// Compute the square root using Tonelli-Shanks algorithm
+/// non_residue must be a quadratic non-residue of the field of the given order
+pub(crate) fn tonelli_shanks<T : FieldElement>(a : &T, non_residue : &T, order : &BigInt) -> Option<T> {
+    if a.is_zero() {
+        return Some(a.clone());
+    }
+
+    // order - 1 = 2^e * m with odd m
+    let one = a.pow(&BigInt::zero());
+    let e = (order - BigInt::one()).trailing_zeros().unwrap();
+    let m : BigInt = (order - BigInt::one()) >> e;
+
+    let mut c = non_residue.pow(&m);
+    let mut t = a.pow(&m);
+    let mut result = a.pow(&((&m + BigInt::one()) >> 1));
+    let mut s = e;
+
+    while t != one {
+        // find the least i such that t^{2^i} = 1
+        let mut i = 0;
+        let mut tmp = t.clone();
+        while tmp != one {
+            tmp = tmp.clone() * tmp.clone();
+            i += 1;
+            if i == s {
+                return None;
+            }
+        }
+
+        let mut b = c;
+        for _ in 0..(s - i - 1) {
+            b = b.clone() * b.clone();
+        }
+        result = result * b.clone();
+        c = b.clone() * b;
+        t = t * c.clone();
+        s = i;
+    }
+
+    Some(result)
+}
+
+/// Compute the Jacobi symbol (a / n) for an odd n with the binary algorithm
+/// It only uses shifts, subtractions and comparisons, and it's the Legendre symbol if n is prime
+pub fn jacobi(a : &BigUint, n : &BigUint) -> i8 {
+    assert!(n.is_odd(), "The Jacobi symbol is defined for odd n");
+    let low_bits = |x : &BigUint| x.iter_u64_digits().next().unwrap_or(0);
+
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        // (2 / n) = -1 iff n = 3, 5 mod 8
+        let twos = a.trailing_zeros().unwrap();
+        a >>= twos;
+        if twos % 2 == 1 && matches!(low_bits(&n) & 7, 3 | 5) {
+            result = -result;
+        }
+
+        // quadratic reciprocity for odd a and n
+        if a < n {
+            std::mem::swap(&mut a, &mut n);
+            if low_bits(&a) & 3 == 3 && low_bits(&n) & 3 == 3 {
+                result = -result;
+            }
+        }
+        a -= &n;
+    }
+
+    match n.is_one() {
+        true => result,
+        false => 0,
+    }
+}
+
+/// Compute an n-th root in the finite field
+/// x -> x^{n / d} permutes the d-th powers for d = gcd(n, order - 1),
+/// so it reduces to d-th roots which are taken one prime factor at a time
+pub(crate) fn nth_root<F, R : RngCore + ?Sized>(field : &F, a : &F::Element, n : u64, rng : &mut R) -> Option<F::Element>
+where F : Field<Element : ElementEncoding> {
+    assert!(n > 0, "There is no 0-th root");
+    if a.is_zero() {
+        return Some(a.clone());
+    }
+
+    let group_order = field.order() - BigInt::one();
+    let d = BigInt::from(n).gcd(&group_order);
+    let cofactor = &group_order / &d;
+
+    // a is a n-th power iff a^{(order - 1) / d} = 1
+    if a.pow(&cofactor) != field.one() {
+        return None;
+    }
+
+    // x^n = a for x^d = a^e with e = (n / d)^{-1} mod (order - 1) / d
+    let e = match cofactor.is_one() {
+        true => BigInt::zero(),
+        false => (BigInt::from(n) / &d).modinv(&cofactor).unwrap(),
+    };
+    let mut root = a.pow(&e);
+    let d : u64 = d.try_into().unwrap();
+    for (r, multiplicity) in num_prime::nt_funcs::factorize64(d) {
+        for _ in 0..multiplicity {
+            root = prime_root(field, &root, r, &group_order, rng)?;
+        }
+    }
+
+    Some(root)
+}
+
+/// Compute an r-th root for a prime r dividing group_order, the order of the multiplicative group
+/// It's the r-adic version of Tonelli-Shanks algorithm,
+/// and each r-adic digit is found by baby-step giant-step in O(sqrt(r)) operations
+fn prime_root<F, R : RngCore + ?Sized>(field : &F, a : &F::Element, r : u64, group_order : &BigInt, rng : &mut R) -> Option<F::Element>
+where F : Field<Element : ElementEncoding> {
+    let one = field.one();
+    let r_big = BigInt::from(r);
+
+    // group_order = r^s * t with t prime to r
+    let mut s = 0;
+    let mut t = group_order.clone();
+    while (&t % &r_big).is_zero() {
+        t /= &r_big;
+        s += 1;
+    }
+
+    // x^r = a * b where b is in the r-Sylow subgroup
+    let alpha = match t.is_one() {
+        true => BigInt::zero(),
+        false => r_big.modinv(&t).unwrap(),
+    };
+    let x = a.pow(&alpha);
+    let b = x.pow(&r_big) * a.inv();
+
+    // g generates the r-Sylow subgroup and gamma is a primitive r-th root of unity
+    let g = loop {
+        let c = field.rand_with_rng(rng, None);
+        if !c.is_zero() && c.pow(&(group_order / &r_big)) != one {
+            break c.pow(&t);
+        }
+    };
+    let g_inv = g.inv();
+    let gamma = g.pow(&r_big.pow(s - 1));
+
+    // the baby steps gamma^j for j < m, keyed by their encoding, and the giant step gamma^{-m}
+    let m = r.sqrt() + 1;
+    let mut baby_steps = HashMap::new();
+    let mut power = one.clone();
+    for j in 0..m {
+        baby_steps.entry(power.to_bytes()).or_insert(j);
+        power = power * gamma.clone();
+    }
+    let giant_step = power.inv();
+
+    // the discrete logarithm b = g^e computed r-adic digit by digit
+    let mut e = BigInt::zero();
+    for i in 0..s {
+        // h = gamma^digit with digit = k * m + j
+        let mut h = (b.clone() * g_inv.pow(&e)).pow(&r_big.pow(s - 1 - i));
+        let mut digit = None;
+        for k in 0..m {
+            if let Some(j) = baby_steps.get(&h.to_bytes()) {
+                digit = Some(k * m + j);
+                break;
+            }
+            h = h * giant_step.clone();
+        }
+        e += BigInt::from(digit?) * r_big.pow(i);
+    }
+
+    // a is a r-th power iff r divides e, then (x * g^{-e / r})^r = a
+    if !(&e % &r_big).is_zero() {
+        return None;
+    }
+    Some(x * g_inv.pow(&(e / r_big)))
+}
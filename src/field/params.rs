@@ -0,0 +1,24 @@
+//! Compile-time prime fields for standard parameter sets
+use super::fp_const::{ConstPrimeField, ConstField2};
+use crate::prime_field;
+
+prime_field!(
+    /// SIKE p434 = 2^216 * 3^137 - 1
+    pub P434, 7, "0x2341f271773446cfc5fd681c520567bc65c783158aea3fdc1767ae2ffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+);
+
+prime_field!(
+    /// SIKE p751 = 2^372 * 3^239 - 1
+    pub P751, 12, "0x6fe5d541f71c0e12909f97badc668562b5045cb25748084e9867d6ebe876da959b1a13f7cc76e3ec968549f878a8eeafffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+);
+
+prime_field!(
+    /// CSIDH-512 p = 4 * (3 * 5 * ... * 373) * 587 - 1
+    pub Csidh512, 8, "0x65b48e8f740f89bffc8ab0d15e3e4c4ab42d083aedc88c425afbfcc69322c9cda7aac6c567f35507516730cc1f0b4f25c2721bf457aca8351b81b90533c6c87b"
+);
+
+pub type Fp434 = ConstPrimeField<P434, 7>;
+pub type Fp434Sqr = ConstField2<P434, 7>;
+pub type Fp751 = ConstPrimeField<P751, 12>;
+pub type Fp751Sqr = ConstField2<P751, 12>;
+pub type FpCsidh512 = ConstPrimeField<Csidh512, 8>;
@@ -1,4 +1,5 @@
 //! Implementation of isogeny computation between two abelian varieties
 
 pub mod rational_isogeny;
-pub mod richelot_isogeny;
\ No newline at end of file
+pub mod richelot_isogeny;
+pub mod modular_polynomial;
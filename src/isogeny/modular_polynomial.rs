@@ -0,0 +1,192 @@
+//! The classical modular polynomials Φ_ℓ(X, Y)
+//!
+//! Φ_ℓ(j(E), j(E')) = 0 iff there is a cyclic ℓ-isogeny E -> E', so the roots of Φ_ℓ(j, Y)
+//! are the neighbours of j in the ℓ-isogeny graph.
+//! Φ_2, Φ_3, Φ_5 and Φ_7 are shipped with the crate, larger ones are loaded from files in the format
+//! of Sutherland's database : one line `[i,j] c` for every i >= j, where the integer c is the coefficient
+//! of X^i Y^j and of X^j Y^i. Blank lines are ignored.
+use crate::field::Field;
+use crate::poly::Polynomial;
+use crate::poly::bivariate::BivariatePolynomial;
+use crate::sage::{self, ParseError};
+use std::path::Path;
+use std::str::FromStr;
+use std::{fs, io};
+use num::BigInt;
+use rand::RngCore;
+
+/// The levels ℓ of the shipped modular polynomials
+pub const LEVELS : [usize; 4] = [2, 3, 5, 7];
+
+/// The classical modular polynomial Φ_ℓ with integer coefficients
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModularPolynomial {
+    level : usize,
+    coefficient : Vec<(usize, usize, BigInt)>,
+}
+
+impl ModularPolynomial {
+    /// Φ_ℓ for ℓ in [LEVELS](LEVELS)
+    /// None for the other levels, which must be loaded with [from_file](Self::from_file)
+    pub fn new(level : usize) -> Option<Self> {
+        let data = match level {
+            2 => include_str!("data/phi_2.txt"),
+            3 => include_str!("data/phi_3.txt"),
+            5 => include_str!("data/phi_5.txt"),
+            7 => include_str!("data/phi_7.txt"),
+            _ => return None,
+        };
+        Some(data.parse().expect("The shipped modular polynomials are well-formed"))
+    }
+
+    /// Read a file in the format of Sutherland's database
+    /// A malformed file is reported as [InvalidData](std::io::ErrorKind::InvalidData)
+    pub fn from_file<P : AsRef<Path>>(path : P) -> io::Result<Self> {
+        fs::read_to_string(path)?.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// The level ℓ
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// The coefficients (i, j, c) of X^i Y^j and X^j Y^i for i >= j
+    pub fn coefficients(&self) -> &[(usize, usize, BigInt)] {
+        &self.coefficient
+    }
+
+    /// Reduce the coefficients into the field, modulo its characteristic
+    pub fn reduce<F : Field>(&self, field : &F) -> BivariatePolynomial<F> {
+        let mut terms = vec![];
+        for (i, j, c) in &self.coefficient {
+            let c = field.one() * c.clone();
+            if i != j {
+                terms.push((*j, *i, c.clone()));
+            }
+            terms.push((*i, *j, c));
+        }
+        BivariatePolynomial::from_terms(field, &terms)
+    }
+
+    /// The polynomial Φ_ℓ(j, Y) in Y, which is also Φ_ℓ(Y, j) by symmetry
+    pub fn eval<F : Field>(&self, field : &F, j : &F::Element) -> Polynomial<F> {
+        let mut powers = vec![field.one()];
+        for k in 0..=self.level {
+            powers.push(powers[k].clone() * j.clone());
+        }
+
+        let mut coefficient = vec![field.zero(); self.level + 2];
+        for (a, b, c) in &self.coefficient {
+            coefficient[*b] = coefficient[*b].clone() + powers[*a].clone() * c.clone();
+            if a != b {
+                coefficient[*a] = coefficient[*a].clone() + powers[*b].clone() * c.clone();
+            }
+        }
+        Polynomial::new(field, coefficient)
+    }
+
+    /// The roots of Φ_ℓ(j, Y) in the field with multiplicity using the thread-local random number generator
+    /// Over F_{p^2} a supersingular j has ℓ + 1 neighbours, the j-invariants of its ℓ-isogenous curves
+    pub fn neighbours<F : Field>(&self, field : &F, j : &F::Element) -> Vec<F::Element> {
+        self.neighbours_with_rng(field, j, &mut rand::thread_rng())
+    }
+
+    /// The roots of Φ_ℓ(j, Y) in the field with multiplicity using the given random number generator
+    pub fn neighbours_with_rng<F : Field, R : RngCore + ?Sized>(&self, field : &F, j : &F::Element, rng : &mut R) -> Vec<F::Element> {
+        let mut neighbours = vec![];
+        for (factor, multiplicity) in self.eval(field, j).squarefree_factorization() {
+            for root in factor.roots_with_rng(rng) {
+                neighbours.extend(std::iter::repeat_n(root, multiplicity));
+            }
+        }
+        neighbours
+    }
+}
+
+/// Parse the lines `[i,j] c` with i >= j, the level is ℓ = deg_X Φ_ℓ - 1
+impl FromStr for ModularPolynomial {
+    type Err = ParseError;
+    fn from_str(s : &str) -> Result<Self, ParseError> {
+        let mut coefficient = vec![];
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (exponents, c) = line.strip_prefix('[')
+                .and_then(|line| line.split_once(']'))
+                .ok_or(ParseError::InvalidSyntax)?;
+            let (i, j) = exponents.split_once(',').ok_or(ParseError::InvalidSyntax)?;
+            let i : usize = i.trim().parse().map_err(|_| ParseError::InvalidNumber)?;
+            let j : usize = j.trim().parse().map_err(|_| ParseError::InvalidNumber)?;
+            if i < j {
+                return Err(ParseError::InvalidSyntax);
+            }
+            coefficient.push((i, j, sage::parse_integer(c)?));
+        }
+
+        // Φ_ℓ is monic of degree ℓ + 1 in X
+        let degree = coefficient.iter().map(|(i, _, _)| *i).max().ok_or(ParseError::InvalidSyntax)?;
+        if degree < 2 {
+            return Err(ParseError::InvalidSyntax);
+        }
+        Ok(ModularPolynomial {
+            level : degree - 1,
+            coefficient,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::FieldElement;
+    use crate::field::fp::FiniteField;
+    use crate::field::fp2::FiniteField2;
+    use crate::elliptic_curve::mont_curve::MontgomeryCurve;
+    use num::BigUint;
+
+    #[test]
+    fn modular_polynomial_test() {
+        let phi2 = ModularPolynomial::new(2).unwrap();
+        assert_eq!(phi2.level(), 2);
+        assert_eq!(phi2, "[3,0] 1\n[2,2] -1\n[2,1] 1488\n[2,0] -162000\n\
+            [1,1] 40773375\n[1,0] 8748000000\n[0,0] -157464000000000\n".parse().unwrap());
+        assert_eq!("[1,2] 1".parse::<ModularPolynomial>(), Err(ParseError::InvalidSyntax));
+        assert_eq!("[3,0] one".parse::<ModularPolynomial>(), Err(ParseError::InvalidNumber));
+        assert!(ModularPolynomial::from_file("/nonexistent/phi_11.txt").is_err());
+        assert_eq!(ModularPolynomial::new(11), None);
+        assert_eq!(ModularPolynomial::new(4), None);
+
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(430_u32));
+        let c = |n : i64| fp2.gen((&BigInt::from(n), &BigInt::from(0)));
+        for level in LEVELS {
+            let phi = ModularPolynomial::new(level).unwrap();
+            assert_eq!(phi.level(), level);
+            let reduced = phi.reduce(&fp2);
+            assert_eq!((reduced.degree_x(), reduced.degree_y()), (level + 1, level + 1));
+            assert_eq!(reduced.swap(), reduced);
+            assert_eq!(reduced.eval_x(&c(5)), phi.eval(&fp2, &c(5)));
+        }
+
+        // y^2 = x^3 + x is 2-isogenous to y^2 = x^3 + 6x^2 + x through the kernel generated by (i, 0)
+        let j = MontgomeryCurve::new(&fp2, c(0)).j_invariant();
+        let j_prime = MontgomeryCurve::new(&fp2, c(6)).j_invariant();
+        assert_eq!(j, c(1728));
+        assert!(phi2.eval(&fp2, &j).eval(&j_prime).is_zero());
+        assert!(phi2.neighbours(&fp2, &j).contains(&j_prime));
+    }
+
+    #[test]
+    fn neighbours_test() {
+        // 1728 is supersingular since 431 = 3 mod 4, so all its neighbours are in F_{431^2}
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(430_u32));
+        let j = fp2.gen((&BigInt::from(1728), &BigInt::from(0)));
+        for level in LEVELS {
+            let phi = ModularPolynomial::new(level).unwrap();
+            let neighbours = phi.neighbours(&fp2, &j);
+            assert_eq!(neighbours.len(), level + 1);
+            for j_prime in &neighbours {
+                assert!(phi.neighbours(&fp2, j_prime).contains(&j));
+            }
+        }
+    }
+}
@@ -3,16 +3,16 @@ use crate::elliptic_curve::mont_curve::{MontgomeryCurve, MontgomeryCurvePoint};
 use crate::field::{Field, FieldElement};
 
 /// The rational isogeny
-struct RationalIsogeny<'a, F: Field<'a> + 'a> {
-    domain_curve : MontgomeryCurve<'a, F>,
-    codomain_curve : Option<MontgomeryCurve<'a, F>>,
-    kernel_generator_point : MontgomeryCurvePoint<'a, F>,
-    domain_basis : Option<(MontgomeryCurvePoint<'a, F>, MontgomeryCurvePoint<'a, F>)>,
-    codomain_basis : Option<(MontgomeryCurvePoint<'a, F>, MontgomeryCurvePoint<'a, F>)>,
+struct RationalIsogeny<F: Field> {
+    domain_curve : MontgomeryCurve<F>,
+    codomain_curve : Option<MontgomeryCurve<F>>,
+    kernel_generator_point : MontgomeryCurvePoint<F>,
+    domain_basis : Option<(MontgomeryCurvePoint<F>, MontgomeryCurvePoint<F>)>,
+    codomain_basis : Option<(MontgomeryCurvePoint<F>, MontgomeryCurvePoint<F>)>,
 }
 
-impl<'a, F: Field<'a> + 'a> RationalIsogeny<'a, F> {
-    pub fn new(domain_curve : &MontgomeryCurve<'a, F>, kernel_generator_point : &MontgomeryCurvePoint<'a, F>) -> Self {
+impl<F: Field> RationalIsogeny<F> {
+    pub fn new(domain_curve : &MontgomeryCurve<F>, kernel_generator_point : &MontgomeryCurvePoint<F>) -> Self {
         // TODO : compute the codomain curve and pair of corresponding basis of domain and codomain curves
         RationalIsogeny {
             domain_curve : domain_curve.clone(),
@@ -23,7 +23,7 @@ impl<'a, F: Field<'a> + 'a> RationalIsogeny<'a, F> {
         }
     }
 
-    pub fn eval(&self, point : MontgomeryCurvePoint<'a, F>) -> MontgomeryCurvePoint<'a, F> {
+    pub fn eval(&self, point : MontgomeryCurvePoint<F>) -> MontgomeryCurvePoint<F> {
         // TODO : evaluate the input point on the domain curve
         point
     }
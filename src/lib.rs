@@ -7,4 +7,6 @@ pub mod elliptic_curve;
 pub mod group;
 pub mod algebra;
 pub mod isogeny;
-pub mod endomorphism;
\ No newline at end of file
+pub mod endomorphism;
+pub mod encoding;
+pub mod sage;
\ No newline at end of file
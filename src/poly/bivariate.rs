@@ -0,0 +1,360 @@
+//! Bivariate polynomial with field coefficient
+//!
+//! A polynomial in X and Y is stored as a polynomial in X whose coefficients are polynomials in Y.
+use super::Polynomial;
+use crate::field::Field;
+use crate::sage;
+use std::ops::{Add, Sub, Mul, Neg};
+use std::cmp::max;
+use std::fmt;
+
+/// BivariatePolynomial owns a handle of a [Field](crate::field::Field) like [Polynomial](super::Polynomial)
+#[derive(Clone)]
+pub struct BivariatePolynomial<F> where F: Field {
+    field : F,
+    coefficient : Vec<Polynomial<F>>,
+}
+
+/// The layout of the Display with the Debug of the coefficients
+impl<F> fmt::Debug for BivariatePolynomial<F> where F: Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_terms(f, |c| format!("{c:?}"))
+    }
+}
+
+/// Sage syntax in the variables x and y by decreasing total degree, e.g. `x^2 + 2*x*y - y + 1`
+impl<F> fmt::Display for BivariatePolynomial<F> where F: Field, F::Element: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_terms(f, |c| c.to_string())
+    }
+}
+
+impl<F> BivariatePolynomial<F> where F: Field {
+    /// coefficient[i] is the polynomial in Y of X^i, the leading zeros are removed
+    pub fn new(field : &F, coefficient : Vec<Polynomial<F>>) -> Self {
+        let mut result = BivariatePolynomial {
+            field : field.clone(),
+            coefficient,
+        };
+        result.normalize();
+        result
+    }
+
+    /// The sum of the terms c * X^i * Y^j given as (i, j, c)
+    pub fn from_terms(field : &F, terms : &[(usize, usize, F::Element)]) -> Self {
+        let mut coefficient = vec![Polynomial::new(field, vec![]); terms.iter().map(|(i, _, _)| i + 1).max().unwrap_or(0)];
+        for (i, j, c) in terms {
+            coefficient[*i] = coefficient[*i].clone() + Polynomial::monomial(field, c.clone(), *j);
+        }
+        BivariatePolynomial::new(field, coefficient)
+    }
+
+    /// The univariate polynomial f(X) as a polynomial in X
+    pub fn from_x(f : &Polynomial<F>) -> Self {
+        let field = &f.field;
+        BivariatePolynomial::new(field, f.coefficients().iter().map(|c| Polynomial::new(field, vec![c.clone()])).collect())
+    }
+
+    /// The univariate polynomial f(Y) as a polynomial in Y
+    pub fn from_y(f : &Polynomial<F>) -> Self {
+        BivariatePolynomial::new(&f.field, vec![f.clone()])
+    }
+
+    /// The coefficients in Y from the constant term in X
+    pub fn coefficients(&self) -> &[Polynomial<F>] {
+        &self.coefficient
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.is_empty()
+    }
+
+    /// The degree in X, 0 for the zero polynomial
+    pub fn degree_x(&self) -> usize {
+        self.coefficient.len().saturating_sub(1)
+    }
+
+    /// The degree in Y, 0 for the zero polynomial
+    pub fn degree_y(&self) -> usize {
+        self.coefficient.iter().map(|c| c.degree()).max().unwrap_or(0)
+    }
+
+    /// Remove the leading zeros in X
+    pub fn normalize(&mut self) {
+        let len = self.coefficient.iter().rposition(|c| !c.is_zero()).map_or(0, |i| i + 1);
+        self.coefficient.truncate(len);
+    }
+
+    /// The polynomial f(a, Y) in Y
+    pub fn eval_x(&self, a : &F::Element) -> Polynomial<F> {
+        self.coefficient.iter().rev().fold(Polynomial::new(&self.field, vec![]), |acc, c| acc.scale(a) + c.clone())
+    }
+
+    /// The polynomial f(X, b) in X
+    pub fn eval_y(&self, b : &F::Element) -> Polynomial<F> {
+        Polynomial::new(&self.field, self.coefficient.iter().map(|c| c.eval(b)).collect())
+    }
+
+    /// The value f(a, b)
+    pub fn eval(&self, a : &F::Element, b : &F::Element) -> F::Element {
+        self.eval_y(b).eval(a)
+    }
+
+    /// Exchange the variables, f(Y, X)
+    pub fn swap(&self) -> Self {
+        let mut coefficient = vec![vec![self.field.zero(); self.coefficient.len()]; self.degree_y() + 1];
+        for (i, c) in self.coefficient.iter().enumerate() {
+            for (j, c) in c.coefficients().iter().enumerate() {
+                coefficient[j][i] = c.clone();
+            }
+        }
+        BivariatePolynomial::new(&self.field, coefficient.into_iter().map(|c| Polynomial::new(&self.field, c)).collect())
+    }
+
+    /// The resultant in X, a polynomial in Y which vanishes at the Y coordinates of the common zeros
+    /// It's the subresultant algorithm over F[Y] (Cohen, Algorithm 3.3.7), so it works in any characteristic
+    pub fn resultant_x(&self, other : &Self) -> Polynomial<F> {
+        let one = Polynomial::new(&self.field, vec![self.field.one()]);
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::new(&self.field, vec![]);
+        }
+
+        // Res(A, B) = (-1)^{deg A * deg B} Res(B, A)
+        let (mut a, mut b, mut negative) = match self.degree_x() >= other.degree_x() {
+            true => (self.clone(), other.clone(), false),
+            false => (other.clone(), self.clone(), self.degree_x() * other.degree_x() % 2 == 1),
+        };
+        let (mut g, mut h) = (one.clone(), one.clone());
+        while b.degree_x() > 0 {
+            let delta = a.degree_x() - b.degree_x();
+            if a.degree_x() % 2 == 1 && b.degree_x() % 2 == 1 {
+                negative = !negative;
+            }
+
+            let r = a.pseudo_rem(&b);
+            if r.is_zero() {
+                return Polynomial::new(&self.field, vec![]);
+            }
+            a = b;
+            b = r.div_exact(&(g.clone() * pow(&h, delta)));
+            g = a.coefficient.last().unwrap().clone();
+            // h = g^delta / h^{delta - 1}
+            h = match delta {
+                0 => h,
+                _ => exact_quotient(&pow(&g, delta), &pow(&h, delta - 1)),
+            };
+        }
+
+        // B is a nonzero constant in X : h = lc(B)^{deg A} / h^{deg A - 1}
+        let n = a.degree_x();
+        let result = match n {
+            0 => one,
+            _ => exact_quotient(&pow(&b.coefficient[0], n), &pow(&h, n - 1)),
+        };
+        match negative {
+            true => -result,
+            false => result,
+        }
+    }
+
+    /// The pseudo-remainder in X : lc(B)^{deg A - deg B + 1} * A mod B, whose coefficients stay in F[Y]
+    fn pseudo_rem(&self, other : &Self) -> Self {
+        let n = other.degree_x();
+        let lc = other.coefficient.last().unwrap();
+        let mut remainder = self.coefficient.clone();
+        let mut e = self.degree_x() + 1 - n;
+        while !remainder.is_empty() && remainder.len() > n {
+            // R = lc(B) * R - lc(R) * X^{deg R - deg B} * B
+            let shift = remainder.len() - 1 - n;
+            let lead = remainder.pop().unwrap();
+            for c in remainder.iter_mut() {
+                *c = c.clone() * lc.clone();
+            }
+            for (j, c) in other.coefficient.iter().take(n).enumerate() {
+                remainder[shift + j] = remainder[shift + j].clone() - lead.clone() * c.clone();
+            }
+            while remainder.last().is_some_and(|c| c.is_zero()) {
+                remainder.pop();
+            }
+            e -= 1;
+        }
+
+        let scale = pow(lc, e);
+        BivariatePolynomial::new(&self.field, remainder.into_iter().map(|c| c * scale.clone()).collect())
+    }
+
+    /// Divide every coefficient by c in F[Y], the divisions must be exact
+    fn div_exact(&self, c : &Polynomial<F>) -> Self {
+        BivariatePolynomial::new(&self.field, self.coefficient.iter().map(|a| exact_quotient(a, c)).collect())
+    }
+
+    /// The resultant in Y, a polynomial in X which vanishes at the X coordinates of the common zeros
+    pub fn resultant_y(&self, other : &Self) -> Polynomial<F> {
+        self.swap().resultant_x(&other.swap())
+    }
+
+    /// Write the terms by decreasing total degree with the coefficients written by show
+    fn write_terms<S : Fn(&F::Element) -> String>(&self, f : &mut fmt::Formatter, show : S) -> fmt::Result {
+        let mut terms = vec![];
+        for (i, c) in self.coefficient.iter().enumerate() {
+            for (j, c) in c.coefficients().iter().enumerate() {
+                terms.push((i, j, c));
+            }
+        }
+        terms.sort_by_key(|(i, j, _)| std::cmp::Reverse((i + j, *i)));
+
+        let power = |variable : &str, e : usize| match e {
+            0 => None,
+            1 => Some(variable.to_string()),
+            _ => Some(format!("{variable}^{e}")),
+        };
+        let terms : Vec<(String, String)> = terms.into_iter().map(|(i, j, c)| {
+            let monomial : Vec<String> = [power("x", i), power("y", j)].into_iter().flatten().collect();
+            (show(c), monomial.join("*"))
+        }).collect();
+        sage::write_sum(f, &terms)
+    }
+}
+
+/// The power a^e in F[Y]
+fn pow<F : Field>(a : &Polynomial<F>, e : usize) -> Polynomial<F> {
+    (0..e).fold(Polynomial::new(&a.field, vec![a.field.one()]), |acc, _| acc * a.clone())
+}
+
+/// The quotient a / b in F[Y] of an exact division
+fn exact_quotient<F : Field>(a : &Polynomial<F>, b : &Polynomial<F>) -> Polynomial<F> {
+    let (quotient, remainder) = a.div_rem(b);
+    debug_assert!(remainder.is_zero(), "The division in F[Y] must be exact");
+    quotient
+}
+
+/// Polynomials are kept normalized, so the coefficients are compared
+impl<F> PartialEq for BivariatePolynomial<F> where F: Field {
+    fn eq(&self, other : &Self) -> bool {
+        self.coefficient == other.coefficient
+    }
+}
+
+impl<F> Eq for BivariatePolynomial<F> where F: Field {}
+
+impl<F> Add for BivariatePolynomial<F> where F: Field {
+    type Output = Self;
+    fn add(self, rhs:Self) -> Self::Output {
+        let mut coefficient = vec![Polynomial::new(&self.field, vec![]); max(self.coefficient.len(), rhs.coefficient.len())];
+        for (i, c) in self.coefficient.into_iter().enumerate() {
+            coefficient[i] = coefficient[i].clone() + c;
+        }
+        for (i, c) in rhs.coefficient.into_iter().enumerate() {
+            coefficient[i] = coefficient[i].clone() + c;
+        }
+        BivariatePolynomial::new(&self.field, coefficient)
+    }
+}
+
+impl<F> Neg for BivariatePolynomial<F> where F: Field {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        BivariatePolynomial {
+            field : self.field,
+            coefficient : self.coefficient.into_iter().map(|c| -c).collect(),
+        }
+    }
+}
+
+impl<F> Sub for BivariatePolynomial<F> where F: Field {
+    type Output = Self;
+    fn sub(self, rhs:Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl<F> Mul for BivariatePolynomial<F> where F: Field {
+    type Output = Self;
+    fn mul(self, rhs:Self) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return BivariatePolynomial::new(&self.field, vec![]);
+        }
+
+        let mut coefficient = vec![Polynomial::new(&self.field, vec![]); self.coefficient.len() + rhs.coefficient.len() - 1];
+        for (i, a) in self.coefficient.iter().enumerate() {
+            for (j, b) in rhs.coefficient.iter().enumerate() {
+                coefficient[i + j] = coefficient[i + j].clone() + a.clone() * b.clone();
+            }
+        }
+        BivariatePolynomial::new(&self.field, coefficient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::FieldElement;
+    use crate::field::fp::FiniteField;
+    use num::{BigInt, BigUint};
+
+    #[test]
+    fn bivariate_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        let c = |n : i64| fp.gen(&BigInt::from(n));
+
+        // the circle x^2 + y^2 - 1 and the line x - y meet where 2*y^2 - 1 = 0
+        let circle = BivariatePolynomial::from_terms(&fp, &[(2, 0, c(1)), (0, 2, c(1)), (0, 0, c(-1))]);
+        let line = BivariatePolynomial::from_terms(&fp, &[(1, 0, c(1)), (0, 1, c(-1))]);
+        assert_eq!(circle.to_string(), "x^2 + y^2 + 102");
+        assert_eq!(line.to_string(), "x + 102*y");
+        assert_eq!(circle.resultant_x(&line), Polynomial::new(&fp, vec![c(-1), c(0), c(2)]));
+        assert_eq!(circle.resultant_y(&line), Polynomial::new(&fp, vec![c(-1), c(0), c(2)]));
+        assert_eq!(circle.swap(), circle);
+        assert_eq!(line.swap(), -line.clone());
+
+        // evaluation in either variable
+        let f = circle.clone() * line.clone() + BivariatePolynomial::from_terms(&fp, &[(1, 3, c(5))]);
+        assert_eq!((f.degree_x(), f.degree_y()), (3, 3));
+        for (a, b) in [(2, 3), (0, 7), (11, 0)] {
+            let (a, b) = (c(a), c(b));
+            let value = circle.eval(&a, &b) * line.eval(&a, &b) + c(5) * a.clone() * b.pow(&BigInt::from(3));
+            assert_eq!(f.eval(&a, &b), value);
+            assert_eq!(f.eval_x(&a).eval(&b), value);
+            assert_eq!(f.swap().eval(&b, &a), value);
+        }
+        assert_eq!(f.clone() - f.clone(), BivariatePolynomial::new(&fp, vec![]));
+        let g = BivariatePolynomial::from_x(&Polynomial::new(&fp, vec![c(1), c(2)]));
+        assert_eq!(g, BivariatePolynomial::from_y(&Polynomial::new(&fp, vec![c(1), c(2)])).swap());
+
+        // the resultant agrees with the univariate resultants of the specializations
+        let h = BivariatePolynomial::from_terms(&fp, &[(2, 1, c(3)), (1, 0, c(1)), (0, 2, c(7)), (0, 0, c(2))]);
+        let r = f.resultant_x(&h);
+        for b in [20, 50, 101].map(c) {
+            assert_eq!(r.eval(&b), f.eval_y(&b).resultant_with_degrees(&h.eval_y(&b), 3, 2));
+        }
+
+        // a common factor makes the resultant vanish
+        assert!((circle.clone() * line.clone()).resultant_x(&(line.clone() * g.clone())).is_zero());
+    }
+
+    #[test]
+    fn small_characteristic_resultant_test() {
+        // the degree bound 8 of the resultant exceeds the characteristic 3
+        let fp = FiniteField::new(&BigUint::from(3_u32));
+        let c = |n : i64| fp.gen(&BigInt::from(n));
+
+        // Res_X(X^3 + Y^4 + 1, X^2 - Y) = (Y^4 + 1)^2 - Y^3 = Y^8 + 2*Y^4 - Y^3 + 1
+        let f = BivariatePolynomial::from_terms(&fp, &[(3, 0, c(1)), (0, 4, c(1)), (0, 0, c(1))]);
+        let g = BivariatePolynomial::from_terms(&fp, &[(2, 0, c(1)), (0, 1, c(-1))]);
+        let expected = Polynomial::new(&fp, vec![c(1), c(0), c(0), c(-1), c(2), c(0), c(0), c(0), c(1)]);
+        assert_eq!(f.resultant_x(&g), expected);
+        // deg f * deg g is even
+        assert_eq!(g.resultant_x(&f), expected);
+
+        let circle = BivariatePolynomial::from_terms(&fp, &[(2, 0, c(1)), (0, 2, c(1)), (0, 0, c(-1))]);
+        let line = BivariatePolynomial::from_terms(&fp, &[(1, 0, c(1)), (0, 1, c(-1))]);
+        assert_eq!(circle.resultant_x(&line), Polynomial::new(&fp, vec![c(-1), c(0), c(2)]));
+        for b in [0, 1, 2].map(c) {
+            assert_eq!(f.resultant_x(&g).eval(&b), f.eval_y(&b).resultant_with_degrees(&g.eval_y(&b), 3, 2));
+        }
+
+        // odd degrees in X flip the sign
+        let h = BivariatePolynomial::from_terms(&fp, &[(1, 2, c(1)), (0, 0, c(1))]);
+        assert_eq!(f.resultant_x(&h), -h.resultant_x(&f));
+    }
+}
@@ -0,0 +1,1223 @@
+//! Polynomial with field coefficient
+pub mod bivariate;
+
+use crate::field::{Field, FieldElement, FieldParsing, batch_inv};
+use crate::field::extension::{ExtensionField, ExtensionFieldElement};
+use std::ops::{Add, Sub, Mul, Neg, Div, Rem};
+use std::cmp::max;
+use std::fmt;
+use crate::sage::{self, ParseError};
+use num::{BigInt, One, Integer};
+use num::bigint::Sign;
+use rand::RngCore;
+
+/// Polynomial owns a handle of a [Field](crate::field::Field) for [FieldElement](crate::field::FieldElement)
+#[derive(Clone)]
+pub struct Polynomial<F> where F: Field {
+    field : F,
+    coefficient : Vec<F::Element>,
+}
+
+/// The layout of the Display with the Debug of the coefficients
+impl<F> fmt::Debug for Polynomial<F> where F: Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_terms(f, "x", |c| format!("{c:?}"))
+    }
+}
+
+/// Sage syntax in the variable x, e.g. `(6*i + 3)*x^2 + x + 1`
+impl<F> fmt::Display for Polynomial<F> where F: Field, F::Element: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_in(f, "x")
+    }
+}
+
+
+impl<F> Polynomial<F> where F : Field {
+    /// The coefficients are given from the constant term, the leading zeros are removed
+    pub fn new(field : &F, coefficient : Vec<F::Element>) -> Self {
+        let mut result = Polynomial {
+            field : field.clone(),
+            coefficient,
+        };
+        result.normalize();
+        result
+    }
+
+    /// The monomial c * x^n
+    pub fn monomial(field : &F, c : F::Element, n : usize) -> Self {
+        let mut coefficient = vec![field.zero(); n];
+        coefficient.push(c);
+        Polynomial::new(field, coefficient)
+    }
+
+    /// The monic polynomial prod (x - r) for the roots r, multiplied along a subproduct tree
+    pub fn from_roots(field : &F, roots : &[F::Element]) -> Self {
+        SubproductTree::new(field, roots).root()
+    }
+
+    /// The value at a by Horner's rule
+    pub fn eval(&self, a : &F::Element) -> F::Element {
+        self.coefficient.iter().rev().fold(self.field.zero(), |acc, c| acc * a.clone() + c.clone())
+    }
+
+    /// The value at an element a of an extension, the coefficients are mapped by embed
+    pub fn eval_with<T : FieldElement>(&self, a : &T, embed : impl Fn(&F::Element) -> T) -> T {
+        let mut coefficients = self.coefficient.iter().rev();
+        let leading = embed(&coefficients.next().cloned().unwrap_or_else(|| self.field.zero()));
+        coefficients.fold(leading, |acc, c| acc * a.clone() + embed(c))
+    }
+
+    /// The value at an element of an [ExtensionField](crate::field::extension::ExtensionField) of the field
+    pub fn eval_extension(&self, field : &ExtensionField<F>, a : &ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+        self.eval_with(a, |c| field.embed(c))
+    }
+
+    /// The formal derivative
+    pub fn derivative(&self) -> Self {
+        Polynomial::new(&self.field, self.coefficient.iter().enumerate().skip(1)
+            .map(|(i, c)| c.clone() * BigInt::from(i))
+            .collect())
+    }
+
+    /// The composition self(other) by Horner's rule
+    pub fn compose(&self, other : &Self) -> Self {
+        self.coefficient.iter().rev().fold(Polynomial::new(&self.field, vec![]), |acc, c| {
+            acc * other.clone() + Polynomial::new(&self.field, vec![c.clone()])
+        })
+    }
+
+    /// The reversal x^d * self(1 / x) for the degree d
+    pub fn reverse(&self) -> Self {
+        Polynomial::new(&self.field, self.coefficient.iter().rev().cloned().collect())
+    }
+
+    /// Multiply by a scalar
+    pub fn scale(&self, c : &F::Element) -> Self {
+        Polynomial::new(&self.field, self.coefficient.iter().map(|x| x.clone() * c.clone()).collect())
+    }
+
+    /// The coefficients from the constant term
+    pub fn coefficients(&self) -> &[F::Element] {
+        &self.coefficient
+    }
+
+    /// Write it in Sage syntax from the leading term in the given variable
+    pub(crate) fn fmt_in(&self, f : &mut fmt::Formatter, variable : &str) -> fmt::Result where F::Element : fmt::Display {
+        self.write_terms(f, variable, |c| c.to_string())
+    }
+
+    /// Write the terms from the leading one with the coefficients written by show
+    fn write_terms<S : Fn(&F::Element) -> String>(&self, f : &mut fmt::Formatter, variable : &str, show : S) -> fmt::Result {
+        let terms : Vec<(String, String)> = self.coefficient.iter().enumerate().rev().map(|(i, c)| {
+            let monomial = match i {
+                0 => String::new(),
+                1 => variable.to_string(),
+                _ => format!("{variable}^{i}"),
+            };
+            (show(c), monomial)
+        }).collect();
+        sage::write_sum(f, &terms)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.len() == 0
+    }
+
+    /// The degree, 0 for the zero polynomial
+    pub fn degree(&self) -> usize {
+        match self.is_zero() {
+            true => 0,
+            false => self.coefficient.len() - 1,
+        }
+    }
+
+    /// Remove the leading zeros
+    /// The constructors and the operations keep polynomials normalized, so the zero polynomial has no coefficient
+    pub fn normalize(&mut self) {
+        if Some(&self.field.zero()) == self.coefficient.last() {
+            let len = self.coefficient.iter().rposition(|d| d.clone() != self.field.zero()).map_or(0, |i| i + 1);
+            self.coefficient.truncate(len);
+        }
+
+        if self.coefficient.len() < self.coefficient.capacity() / 4 {
+            self.coefficient.shrink_to_fit();
+        }
+    }
+
+
+    /// The leading coefficient, zero for the zero polynomial
+    pub fn leading_coefficient(&self) -> F::Element {
+        self.coefficient.last().cloned().unwrap_or_else(|| self.field.zero())
+    }
+
+    /// Divide by the leading coefficient, the zero polynomial stays zero
+    pub fn monic(&self) -> Self {
+        let mut result = self.clone();
+        if result.is_zero() {
+            return result;
+        }
+
+        let lc_inv = result.leading_coefficient().inv();
+        for c in result.coefficient.iter_mut() {
+            *c = c.clone() * lc_inv.clone();
+        }
+        result
+    }
+
+    /// Euclidean division : self = quotient * rhs + remainder with deg(remainder) < deg(rhs)
+    /// rhs must be nonzero or it will panic
+    /// Above [NEWTON_DIVISION_THRESHOLD](NEWTON_DIVISION_THRESHOLD) it takes O(M(n)) operations by Newton's iteration
+    pub fn div_rem(&self, rhs : &Self) -> (Self, Self) {
+        assert!(!rhs.is_zero(), "Can't devide by zero");
+
+        if self.coefficient.len() < rhs.coefficient.len() {
+            return (Polynomial::new(&self.field, vec![]), self.clone());
+        }
+        let quotient_len = self.coefficient.len() - rhs.coefficient.len() + 1;
+        match quotient_len.min(rhs.coefficient.len()) < NEWTON_DIVISION_THRESHOLD {
+            true => self.div_rem_schoolbook(rhs),
+            false => self.div_rem_newton(rhs),
+        }
+    }
+
+    /// Karatsuba's multiplication with the schoolbook one below the given length,
+    /// which is [KARATSUBA_THRESHOLD](KARATSUBA_THRESHOLD) for the Mul operator
+    #[doc(hidden)]
+    pub fn mul_with_threshold(&self, rhs : &Self, threshold : usize) -> Self {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial{field:self.field.clone(), coefficient:vec![]}
+        }
+
+        let result_coeff = karatsuba(&self.coefficient, &rhs.coefficient, &self.field.zero(), threshold);
+        Polynomial::new(&self.field, result_coeff)
+    }
+
+    /// The division from the leading coefficient, deg(self) >= deg(rhs)
+    #[doc(hidden)]
+    pub fn div_rem_schoolbook(&self, rhs : &Self) -> (Self, Self) {
+        let mut remainder = self.clone();
+
+        // eliminate the leading coefficient of the remainder in place, from the top
+        let lc_inv = rhs.leading_coefficient().inv();
+        let shift = remainder.coefficient.len() - rhs.coefficient.len();
+        let mut quotient = vec![self.field.zero(); shift + 1];
+        for i in (0..=shift).rev() {
+            let q = remainder.coefficient[i + rhs.degree()].clone() * lc_inv.clone();
+            if !q.is_zero() {
+                for (j, c) in rhs.coefficient.iter().enumerate() {
+                    remainder.coefficient[i + j] = remainder.coefficient[i + j].clone() - q.clone() * c.clone();
+                }
+            }
+            quotient[i] = q;
+        }
+        remainder.coefficient.truncate(rhs.degree());
+        remainder.normalize();
+
+        (Polynomial::new(&self.field, quotient), remainder)
+    }
+
+    /// The division by the reversals : rev(quotient) = rev(self) / rev(rhs) mod x^k for k = deg(self) - deg(rhs) + 1,
+    /// where the inverse of rev(rhs) is computed by Newton's iteration, deg(self) >= deg(rhs)
+    #[doc(hidden)]
+    pub fn div_rem_newton(&self, rhs : &Self) -> (Self, Self) {
+        let zero = self.field.zero();
+        let k = self.coefficient.len() - rhs.coefficient.len() + 1;
+        let reversed : Vec<_> = self.coefficient.iter().rev().take(k).cloned().collect();
+        let rhs_reversed : Vec<_> = rhs.coefficient.iter().rev().take(k).cloned().collect();
+
+        let mut quotient = karatsuba(&reversed, &inv_series(&rhs_reversed, k, &self.field.one()), &zero, KARATSUBA_THRESHOLD);
+        quotient.resize(k, zero);
+        quotient.reverse();
+        let quotient = Polynomial::new(&self.field, quotient);
+        let remainder = self.clone() - rhs.clone() * quotient.clone();
+
+        (quotient, remainder)
+    }
+
+    /// The monic greatest common divisor, zero if both are zero
+    pub fn gcd(&self, other : &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let (_, r) = a.div_rem(&b);
+            a = b;
+            b = r;
+        }
+        a.monic()
+    }
+
+    /// Extended Euclidean algorithm : (g, s, t) with s * self + t * other = g for the monic gcd g
+    pub fn xgcd(&self, other : &Self) -> (Self, Self, Self) {
+        let zero = Polynomial::new(&self.field, vec![]);
+        let one = Polynomial::new(&self.field, vec![self.field.one()]);
+
+        // r_i = s_i * self + t_i * other
+        let (mut r0, mut s0, mut t0) = (self.clone(), one.clone(), zero.clone());
+        let (mut r1, mut s1, mut t1) = (other.clone(), zero.clone(), one);
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem(&r1);
+            let s = s0 - q.clone() * s1.clone();
+            let t = t0 - q * t1.clone();
+            (r0, s0, t0) = (r1, s1, t1);
+            (r1, s1, t1) = (r, s, t);
+        }
+
+        if r0.is_zero() {
+            return (r0, zero.clone(), zero);
+        }
+        let lc_inv = Polynomial::new(&self.field, vec![r0.leading_coefficient().inv()]);
+        (r0.monic(), s0 * lc_inv.clone(), t0 * lc_inv)
+    }
+
+    /// The monic least common multiple, zero if one of them is zero
+    pub fn lcm(&self, other : &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::new(&self.field, vec![]);
+        }
+        ((self.clone() / self.gcd(other)) * other.clone()).monic()
+    }
+
+    /// The resultant by the Euclidean algorithm, 0 if one of them is zero
+    /// Res(a, b) = (-1)^{mn} lc(b)^{m - s} Res(b, a mod b) for the degrees m, n, s of a, b and a mod b
+    pub fn resultant(&self, other : &Self) -> F::Element {
+        let mut result = self.field.one();
+        let (mut a, mut b) = (self.clone(), other.clone());
+        loop {
+            if a.is_zero() || b.is_zero() {
+                return self.field.zero();
+            }
+
+            let (m, n) = (a.degree(), b.degree());
+            if n == 0 {
+                return result * b.leading_coefficient().pow(&BigInt::from(m));
+            }
+            let r = a.div_rem(&b).1;
+            if r.is_zero() {
+                return self.field.zero();
+            }
+
+            if m * n % 2 == 1 {
+                result = -result;
+            }
+            result = result * b.leading_coefficient().pow(&BigInt::from(m - r.degree()));
+            (a, b) = (b, r);
+        }
+    }
+
+    /// The resultant of the Sylvester matrix for the formal degrees m >= deg(self) and n >= deg(other)
+    /// It's lc(a)^n * prod b(alpha) over the roots alpha of a when deg(a) = m
+    pub fn resultant_with_degrees(&self, other : &Self, m : usize, n : usize) -> F::Element {
+        let (a, b) = (self, other);
+        assert!(a.degree() <= m && b.degree() <= n, "The formal degrees must bound the degrees");
+        match (m, n) {
+            (_, 0) => return b.leading_coefficient().pow(&BigInt::from(m)),
+            (0, _) => return a.leading_coefficient().pow(&BigInt::from(n)),
+            _ => (),
+        }
+        if a.is_zero() || b.is_zero() || (a.degree() < m && b.degree() < n) {
+            return self.field.zero();
+        }
+
+        if b.degree() < n {
+            a.leading_coefficient().pow(&BigInt::from(n - b.degree())) * a.resultant(b)
+        } else {
+            // Res_{m, n}(a, b) = (-1)^{mn} Res_{n, m}(b, a)
+            let k = a.degree();
+            let sign = match (m * n + n * k) % 2 {
+                0 => self.field.one(),
+                _ => -self.field.one(),
+            };
+            sign * b.leading_coefficient().pow(&BigInt::from(m - k)) * a.resultant(b)
+        }
+    }
+
+    /// The discriminant (-1)^{n(n-1)/2} Res(f, f') / lc(f) with the formal degree n - 1 of f'
+    /// It's zero iff f has a multiple root, f must be nonconstant
+    pub fn discriminant(&self) -> F::Element {
+        let n = self.degree();
+        assert!(!self.is_zero() && n >= 1, "The discriminant of a constant is not defined");
+        let result = self.resultant_with_degrees(&self.derivative(), n, n - 1) * self.leading_coefficient().inv();
+        match n * (n - 1) / 2 % 2 {
+            0 => result,
+            _ => -result,
+        }
+    }
+}
+
+impl<F> Polynomial<F> where F : FieldParsing {
+    /// Parse a polynomial in the variable x in the format of its Display
+    pub fn from_str(field : &F, s : &str) -> Result<Self, ParseError> {
+        Self::parse_in(field, s, "x")
+    }
+
+    /// Parse a sum of terms c*v^e in the given variable, the coefficients are parsed by the field
+    pub(crate) fn parse_in(field : &F, s : &str, variable : &str) -> Result<Self, ParseError> {
+        let mut coefficient = vec![];
+        for term in sage::parse_sum(s, &[variable])? {
+            let c = field.element_from_str(term.coefficient)?;
+            let c = match term.negative {
+                true => -c,
+                false => c,
+            };
+            if coefficient.len() <= term.exponent {
+                coefficient.resize(term.exponent + 1, field.zero());
+            }
+            coefficient[term.exponent] = coefficient[term.exponent].clone() + c;
+        }
+
+        Ok(Polynomial::new(field, coefficient))
+    }
+}
+
+/// Root finding and factorization over the finite field F_q
+impl<F> Polynomial<F> where F : Field {
+    fn constant(&self, c : F::Element) -> Self {
+        Polynomial::new(&self.field, vec![c])
+    }
+
+    /// The polynomial x
+    fn x(&self) -> Self {
+        Polynomial::monomial(&self.field, self.field.one(), 1)
+    }
+
+    fn is_one(&self) -> bool {
+        self.coefficient.len() == 1 && self.coefficient[0] == self.field.one()
+    }
+
+    /// Compute self^exponent modulo the modulus by square-and-multiply
+    pub fn pow_mod(&self, exponent : &BigInt, modulus : &Self) -> Self {
+        assert!(exponent.sign() != Sign::Minus, "The exponent must be non-negative");
+        let base = self.div_rem(modulus).1;
+        let mut result = self.constant(self.field.one()).div_rem(modulus).1;
+        for i in (0..exponent.bits()).rev() {
+            result = (result.clone() * result).div_rem(modulus).1;
+            if exponent.bit(i) {
+                result = (result * base.clone()).div_rem(modulus).1;
+            }
+        }
+        result
+    }
+
+    /// The distinct roots in the field using the thread-local random number generator
+    pub fn roots(&self) -> Vec<F::Element> {
+        self.roots_with_rng(&mut rand::thread_rng())
+    }
+
+    /// The distinct roots in the field : the linear factors of gcd(self, x^q - x) are split by Cantor-Zassenhaus
+    /// The zero polynomial must not be given
+    pub fn roots_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> Vec<F::Element> {
+        let f = self.monic();
+        assert!(!f.is_zero(), "The zero polynomial has every element as a root");
+        if f.degree() == 0 {
+            return vec![];
+        }
+
+        let x = self.x();
+        let x_q = x.pow_mod(&self.field.order(), &f);
+        let g = f.gcd(&(x_q - x));
+        g.equal_degree_factorization_with_rng(1, rng).into_iter()
+            .map(|linear| -linear.coefficient[0].clone())
+            .collect()
+    }
+
+    /// The square-free factorization : the pairwise coprime monic square-free g_i with self = lc * prod g_i^{m_i}
+    pub fn squarefree_factorization(&self) -> Vec<(Self, usize)> {
+        let f = self.monic();
+        assert!(!f.is_zero(), "The zero polynomial has no factorization");
+
+        let mut result = vec![];
+        let mut c = f.gcd(&f.derivative());
+        let mut w = f / c.clone();
+        let mut i = 1;
+        while !w.is_one() {
+            let y = w.gcd(&c);
+            let factor = w / y.clone();
+            if !factor.is_one() {
+                result.push((factor, i));
+            }
+            w = y.clone();
+            c = c / y;
+            i += 1;
+        }
+
+        // c = h(x^p) = h^{(p)}(x)^p where h^{(p)} takes the p-th roots of the coefficients
+        if !c.is_one() {
+            let p : usize = self.field.characteristic().try_into().unwrap();
+            let root_exponent = self.field.order() / self.field.characteristic();
+            let root = Polynomial::new(&self.field, c.coefficient.iter().step_by(p).map(|c| c.pow(&root_exponent)).collect());
+            for (g, m) in root.squarefree_factorization() {
+                result.push((g, m * p));
+            }
+        }
+
+        result
+    }
+
+    /// The distinct-degree factorization of a square-free polynomial
+    /// The pairs (g_d, d) where g_d is the product of the monic irreducible factors of degree d
+    pub fn distinct_degree_factorization(&self) -> Vec<(Self, usize)> {
+        let mut f = self.monic();
+        assert!(!f.is_zero(), "The zero polynomial has no factorization");
+
+        let mut result = vec![];
+        let x = self.x();
+        let q = self.field.order();
+        let mut h = x.clone();
+        let mut d = 1;
+        while f.degree() >= 2 * d {
+            // h = x^{q^d} mod f
+            h = h.pow_mod(&q, &f);
+            let g = f.gcd(&(h.clone() - x.clone()));
+            if !g.is_one() {
+                f = f / g.clone();
+                h = h.div_rem(&f).1;
+                result.push((g, d));
+            }
+            d += 1;
+        }
+        if f.degree() > 0 {
+            let degree = f.degree();
+            result.push((f, degree));
+        }
+
+        result
+    }
+
+    /// Split a square-free product of irreducible polynomials of degree d into monic factors
+    /// using the thread-local random number generator
+    pub fn equal_degree_factorization(&self, d : usize) -> Vec<Self> {
+        self.equal_degree_factorization_with_rng(d, &mut rand::thread_rng())
+    }
+
+    /// Cantor-Zassenhaus splitting of a square-free product of irreducible polynomials of degree d
+    /// A random a splits it by gcd(f, a^{(q^d - 1) / 2} - 1), or by the trace of a in characteristic 2
+    pub fn equal_degree_factorization_with_rng<R : RngCore + ?Sized>(&self, d : usize, rng : &mut R) -> Vec<Self> {
+        let f = self.monic();
+        assert!(d > 0 && f.degree().is_multiple_of(d), "The degree must be a multiple of d");
+        if f.degree() == 0 {
+            return vec![];
+        }
+        if f.degree() == d {
+            return vec![f];
+        }
+
+        let q = self.field.order();
+        let one = self.constant(self.field.one());
+        let splitter = loop {
+            let a = Polynomial::new(&self.field, (0..f.degree()).map(|_| self.field.rand_with_rng(rng, None)).collect());
+            let b = match q.is_odd() {
+                true => a.pow_mod(&((q.pow(d as u32) - BigInt::one()) >> 1), &f) - one.clone(),
+                false => {
+                    // a + a^2 + ... + a^{2^{kd - 1}} for q = 2^k
+                    let mut sum = a.div_rem(&f).1;
+                    let mut power = sum.clone();
+                    for _ in 1..(q.bits() - 1) as usize * d {
+                        power = (power.clone() * power).div_rem(&f).1;
+                        sum = sum + power.clone();
+                    }
+                    sum
+                },
+            };
+            let g = f.gcd(&b);
+            if !g.is_zero() && g.degree() > 0 && g.degree() < f.degree() {
+                break g;
+            }
+        };
+
+        let mut result = splitter.equal_degree_factorization_with_rng(d, rng);
+        result.extend((f / splitter).equal_degree_factorization_with_rng(d, rng));
+        result
+    }
+
+    /// The factorization into monic irreducible polynomials using the thread-local random number generator
+    pub fn factor(&self) -> Vec<(Self, usize)> {
+        self.factor_with_rng(&mut rand::thread_rng())
+    }
+
+    /// The factorization into monic irreducible polynomials with their multiplicities, sorted by degree
+    /// The leading coefficient is dropped
+    pub fn factor_with_rng<R : RngCore + ?Sized>(&self, rng : &mut R) -> Vec<(Self, usize)> {
+        let mut result = vec![];
+        for (g, m) in self.squarefree_factorization() {
+            for (h, d) in g.distinct_degree_factorization() {
+                result.extend(h.equal_degree_factorization_with_rng(d, rng).into_iter().map(|factor| (factor, m)));
+            }
+        }
+        result.sort_by_key(|(factor, _)| factor.degree());
+        result
+    }
+
+    /// Rabin's irreducibility test : f of degree n is irreducible iff x^{q^n} = x mod f
+    /// and gcd(x^{q^{n/r}} - x, f) = 1 for every prime r dividing n
+    pub fn is_irreducible(&self) -> bool {
+        let f = self.monic();
+        if f.degree() == 0 {
+            return false;
+        }
+
+        let n = f.degree();
+        let q = self.field.order();
+        let x = self.x();
+        let frobenius = |k : usize| {
+            let mut h = x.clone();
+            for _ in 0..k {
+                h = h.pow_mod(&q, &f);
+            }
+            h
+        };
+
+        for (r, _) in num_prime::nt_funcs::factorize64(n as u64) {
+            let h = frobenius(n / r as usize) - x.clone();
+            if !f.gcd(&h).is_one() {
+                return false;
+            }
+        }
+        (frobenius(n) - x).div_rem(&f).1.is_zero()
+    }
+}
+
+impl<F> Polynomial<F> where F : Field {
+    /// The values at the points using a subproduct tree, in O(M(n) log n) operations
+    pub fn multipoint_evaluate(&self, points : &[F::Element]) -> Vec<F::Element> {
+        SubproductTree::new(&self.field, points).evaluate(self)
+    }
+
+    /// The polynomial of degree less than n taking the values at the n distinct points
+    pub fn interpolate(field : &F, points : &[F::Element], values : &[F::Element]) -> Self {
+        SubproductTree::new(field, points).interpolate(values)
+    }
+}
+
+/// The subproduct tree of the points a_0, ..., a_{n-1}
+/// The leaves are x - a_i and every node is the product of its two children
+#[derive(Clone, Debug)]
+pub struct SubproductTree<F> where F: Field {
+    field : F,
+    levels : Vec<Vec<Polynomial<F>>>,
+}
+
+impl<F> SubproductTree<F> where F: Field {
+    pub fn new(field : &F, points : &[F::Element]) -> Self {
+        let leaves : Vec<Polynomial<F>> = points.iter()
+            .map(|a| Polynomial::new(field, vec![-a.clone(), field.one()]))
+            .collect();
+
+        // an odd node is carried to the next level
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels.last().unwrap().chunks(2).map(|pair| match pair {
+                [left, right] => left.clone() * right.clone(),
+                _ => pair[0].clone(),
+            }).collect();
+            levels.push(next);
+        }
+
+        SubproductTree { field : field.clone(), levels }
+    }
+
+    /// The product of x - a_i
+    pub fn root(&self) -> Polynomial<F> {
+        match self.levels.last().unwrap().first() {
+            Some(root) => root.clone(),
+            None => Polynomial::new(&self.field, vec![self.field.one()]),
+        }
+    }
+
+    /// The values of the polynomial at the points by the remainder tree
+    pub fn evaluate(&self, poly : &Polynomial<F>) -> Vec<F::Element> {
+        if self.levels[0].is_empty() {
+            return vec![];
+        }
+
+        let mut remainders = vec![poly.div_rem(&self.root()).1];
+        for level in self.levels.iter().rev().skip(1) {
+            remainders = level.iter().enumerate()
+                .map(|(i, node)| remainders[i / 2].div_rem(node).1)
+                .collect();
+        }
+        remainders.into_iter()
+            .map(|r| r.coefficient.first().cloned().unwrap_or_else(|| self.field.zero()))
+            .collect()
+    }
+
+    /// Lagrange interpolation : sum of c_i * prod_{j != i} (x - a_j) with c_i = values_i / m'(a_i) for m = prod (x - a_j)
+    /// The points must be distinct or it will panic
+    pub fn interpolate(&self, values : &[F::Element]) -> Polynomial<F> {
+        assert_eq!(values.len(), self.levels[0].len(), "The number of values must be the number of points");
+        if values.is_empty() {
+            return Polynomial::new(&self.field, vec![]);
+        }
+
+        let weights = batch_inv(&self.evaluate(&self.root().derivative()));
+        let mut sums : Vec<Polynomial<F>> = values.iter().zip(weights)
+            .map(|(y, w)| Polynomial::new(&self.field, vec![y.clone() * w]))
+            .collect();
+
+        // (left sum) * (right node) + (right sum) * (left node)
+        for level in self.levels.iter().take(self.levels.len() - 1) {
+            sums = sums.chunks(2).zip(level.chunks(2)).map(|(sum, node)| match (sum, node) {
+                ([sum_l, sum_r], [node_l, node_r]) => sum_l.clone() * node_r.clone() + sum_r.clone() * node_l.clone(),
+                _ => sum[0].clone(),
+            }).collect();
+        }
+        sums.pop().unwrap()
+    }
+}
+
+impl<F> Add for Polynomial<F> where F: Field {
+    type Output = Self;
+    fn add(self, rhs:Self) -> Self::Output {
+        if self.is_zero() {
+            return rhs;
+        }
+
+        if rhs.is_zero() {
+            return self;
+        }
+
+        let mut result_coeff = vec![self.field.zero(); max(self.coefficient.len(), rhs.coefficient.len())];
+
+        for (i, coeff) in self.coefficient.iter().enumerate() {
+            result_coeff[i] = result_coeff[i].clone() + coeff.clone();
+        }
+        for (i, coeff) in rhs.coefficient.iter().enumerate() {
+            result_coeff[i] = result_coeff[i].clone() + coeff.clone();
+        }
+
+        let mut result = Polynomial {
+            field : self.field,
+            coefficient : result_coeff
+        };
+        result.normalize();
+
+        result
+    }
+}
+
+/// Polynomials are kept normalized, so the coefficients are compared
+impl<F> PartialEq for Polynomial<F> where F: Field {
+    fn eq(&self, other : &Self) -> bool {
+        self.coefficient == other.coefficient
+    }
+}
+
+impl<F> Eq for Polynomial<F> where F: Field {}
+
+/// Scalar multiplication
+impl<F> Mul<BigInt> for Polynomial<F> where F: Field {
+    type Output = Self;
+    fn mul(self, rhs : BigInt) -> Self::Output {
+        Polynomial::new(&self.field, self.coefficient.into_iter().map(|c| c * rhs.clone()).collect())
+    }
+}
+
+impl<F> Neg for Polynomial<F> where F: Field {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Polynomial {
+            field : self.field,
+            coefficient : self.coefficient.iter().map(|x| -x.clone()).collect(),
+        }
+    }
+}
+
+impl<F> Sub for Polynomial<F> where F: Field {
+    type Output = Self;
+    fn sub(self, rhs:Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+/// Below this length the schoolbook multiplication is used instead of Karatsuba's
+///
+/// Measured by `cargo bench --bench poly` over p434 : a single level of Karatsuba is faster from the length 12 on,
+/// 216 µs against 347 µs with [FiniteField](crate::field::fp::FiniteField)
+/// and 60 µs against 69 µs with [MontgomeryField](crate::field::fp_mont::MontgomeryField)
+pub const KARATSUBA_THRESHOLD : usize = 12;
+
+/// Below this length of the quotient or the divisor the schoolbook division is used instead of Newton's iteration
+///
+/// Measured by `cargo bench --bench poly` over p434 with a quotient and a divisor of the same length :
+/// Newton's iteration is still slower at 512, 663 ms against 500 ms with [FiniteField](crate::field::fp::FiniteField)
+/// and 138 ms against 122 ms with [MontgomeryField](crate::field::fp_mont::MontgomeryField),
+/// and faster from 768 on, 1.21 s against 1.33 s and 253 ms against 281 ms
+pub const NEWTON_DIVISION_THRESHOLD : usize = 768;
+
+fn add_coefficients<T : FieldElement>(a : &[T], b : &[T]) -> Vec<T> {
+    let (long, short) = match a.len() >= b.len() {
+        true => (a, b),
+        false => (b, a),
+    };
+    let mut result = long.to_vec();
+    for (r, c) in result.iter_mut().zip(short.iter()) {
+        *r = r.clone() + c.clone();
+    }
+    result
+}
+
+/// Add b * x^shift to a
+fn add_shifted<T : FieldElement>(a : &mut [T], b : &[T], shift : usize) {
+    for (r, c) in a[shift..].iter_mut().zip(b.iter()) {
+        *r = r.clone() + c.clone();
+    }
+}
+
+fn schoolbook<T : FieldElement>(a : &[T], b : &[T], zero : &T) -> Vec<T> {
+    let mut result = vec![zero.clone(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        if x.is_zero() {
+            continue;
+        }
+        for (j, y) in b.iter().enumerate() {
+            result[i + j] = result[i + j].clone() + x.clone() * y.clone();
+        }
+    }
+    result
+}
+
+/// Karatsuba's multiplication of the coefficient vectors from the constant term
+/// (a0 + a1 x^m)(b0 + b1 x^m) = a0 b0 + ((a0 + a1)(b0 + b1) - a0 b0 - a1 b1) x^m + a1 b1 x^{2m}
+/// The schoolbook multiplication is used below the threshold
+fn karatsuba<T : FieldElement>(a : &[T], b : &[T], zero : &T, threshold : usize) -> Vec<T> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    if a.len().min(b.len()) < threshold {
+        return schoolbook(a, b, zero);
+    }
+
+    let m = a.len().max(b.len()) / 2;
+    let mut result = vec![zero.clone(); a.len() + b.len() - 1];
+    if a.len() <= m || b.len() <= m {
+        // unbalanced : split the longer one only
+        let (long, short) = match a.len() >= b.len() {
+            true => (a, b),
+            false => (b, a),
+        };
+        let (long0, long1) = long.split_at(m);
+        add_shifted(&mut result, &karatsuba(short, long0, zero, threshold), 0);
+        add_shifted(&mut result, &karatsuba(short, long1, zero, threshold), m);
+        return result;
+    }
+
+    let (a0, a1) = a.split_at(m);
+    let (b0, b1) = b.split_at(m);
+    let z0 = karatsuba(a0, b0, zero, threshold);
+    let z2 = karatsuba(a1, b1, zero, threshold);
+    let mut z1 = karatsuba(&add_coefficients(a0, a1), &add_coefficients(b0, b1), zero, threshold);
+    for (i, c) in z0.iter().enumerate() {
+        z1[i] = z1[i].clone() - c.clone();
+    }
+    for (i, c) in z2.iter().enumerate() {
+        z1[i] = z1[i].clone() - c.clone();
+    }
+
+    add_shifted(&mut result, &z0, 0);
+    add_shifted(&mut result, &z1, m);
+    add_shifted(&mut result, &z2, 2 * m);
+    result
+}
+
+/// The inverse of the power series f modulo x^n by Newton's iteration g <- g * (2 - f * g), f[0] must be nonzero
+/// The precision doubles at each step, so it takes O(M(n)) operations
+fn inv_series<T : FieldElement>(f : &[T], n : usize, one : &T) -> Vec<T> {
+    let zero = one.clone() - one.clone();
+    let mut g = vec![f[0].inv()];
+    while g.len() < n {
+        let len = (2 * g.len()).min(n);
+        let mut h = karatsuba(&f[..f.len().min(len)], &g, &zero, KARATSUBA_THRESHOLD);
+        h.resize(len, zero.clone());
+        for c in h.iter_mut() {
+            *c = -c.clone();
+        }
+        h[0] = h[0].clone() + one.clone() + one.clone();
+
+        g = karatsuba(&g, &h, &zero, KARATSUBA_THRESHOLD);
+        g.resize(len, zero.clone());
+    }
+    g
+}
+
+/// Karatsuba's multiplication above [KARATSUBA_THRESHOLD](KARATSUBA_THRESHOLD)
+impl<F> Mul for Polynomial<F> where F: Field {
+    type Output = Self;
+    fn mul(self, rhs:Self) -> Self::Output {
+        self.mul_with_threshold(&rhs, KARATSUBA_THRESHOLD)
+    }
+}
+
+impl<F> Div for Polynomial<F> where F: Field {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).0
+    }
+}
+
+impl<F> Rem for Polynomial<F> where F: Field {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).1
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::field::ElementEncoding;
+    use crate::field::fp::{FiniteField, FiniteFieldElement};
+    use crate::field::fp2::FiniteField2;
+    use num::{BigInt, BigUint};
+
+    #[test]
+    fn polynomial_test() {
+        // Fp11
+        let fp = FiniteField::new(&BigUint::from(11 as u32));
+        let coeff1 = fp.gen(&BigInt::from(2));
+        let coeff2 = fp.gen(&BigInt::from(-7));
+        let coeff3 = fp.gen(&BigInt::from(4));
+        let poly1 = Polynomial::new(&fp, vec![coeff1.clone(), coeff2.clone(), coeff3.clone()]);
+        let poly2 = Polynomial::new(&fp, vec![coeff3, coeff2, coeff1]);
+        
+
+        let poly3 = poly1.clone() * poly1.clone();
+        println!("({:?}) % ({:?}) = {:?}", poly3, poly1, poly3.clone() % poly1.clone());
+        println!("({:?}) % ({:?}) = {:?}", poly3, poly2, poly3.clone() % poly2.clone());
+    }
+
+    fn random_poly(fp : &FiniteField, degree : usize) -> Polynomial<FiniteField> {
+        let mut coefficient : Vec<_> = (0..degree).map(|_| fp.rand(None)).collect();
+        coefficient.push(fp.one() + fp.one());
+        Polynomial::new(fp, coefficient)
+    }
+
+    #[test]
+    fn div_rem_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        for (deg_a, deg_b) in [(10, 3), (5, 5), (2, 7), (8, 0)] {
+            let a = random_poly(&fp, deg_a);
+            let b = random_poly(&fp, deg_b);
+            let (q, r) = a.div_rem(&b);
+            assert!(r.is_zero() || r.degree() < b.degree());
+            assert_eq!((q.clone() * b.clone() + r.clone()).coefficients(), a.coefficients());
+            assert_eq!((a.clone() / b.clone()).coefficients(), q.coefficients());
+            assert_eq!((a.clone() % b.clone()).coefficients(), r.coefficients());
+        }
+
+        // Newton's iteration agrees with the schoolbook division
+        for (deg_a, deg_b) in [(200, 60), (100, 40), (63, 31), (150, 140), (90, 5)] {
+            let a = random_poly(&fp, deg_a);
+            let b = random_poly(&fp, deg_b);
+            let (q, r) = a.div_rem_newton(&b);
+            let (q_schoolbook, r_schoolbook) = a.div_rem_schoolbook(&b);
+            assert_eq!(q.coefficients(), q_schoolbook.coefficients());
+            assert_eq!(r.coefficients(), r_schoolbook.coefficients());
+            assert!(r.is_zero() || r.degree() < b.degree());
+        }
+        let mut f = random_poly(&fp, 50).coefficients().to_vec();
+        f[0] = fp.one() * BigInt::from(5);
+        let g = super::inv_series(&f, 70, &fp.one());
+        let product = super::schoolbook(&f, &g, &fp.zero());
+        assert_eq!(product[0], fp.one());
+        assert!(product[1..70].iter().all(|c| c.is_zero()));
+
+        // exact division
+        let a = random_poly(&fp, 4);
+        let b = random_poly(&fp, 6);
+        let (q, r) = (a.clone() * b.clone()).div_rem(&a);
+        assert!(r.is_zero());
+        assert_eq!(q.coefficients(), b.coefficients());
+    }
+
+    #[test]
+    fn gcd_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        for _ in 0..20 {
+            let g = random_poly(&fp, 3);
+            let a = g.clone() * random_poly(&fp, 5);
+            let b = g.clone() * random_poly(&fp, 4);
+
+            let d = a.gcd(&b);
+            assert!(d.leading_coefficient() == fp.one());
+            assert!((d.clone() % g.clone()).is_zero());
+            assert!((a.clone() % d.clone()).is_zero() && (b.clone() % d.clone()).is_zero());
+
+            let (e, s, t) = a.xgcd(&b);
+            assert_eq!(e.coefficients(), d.coefficients());
+            assert_eq!((s * a.clone() + t * b.clone()).coefficients(), d.coefficients());
+
+            // gcd * lcm = a * b up to a constant
+            let l = a.lcm(&b);
+            assert_eq!((d * l).coefficients(), (a * b).monic().coefficients());
+        }
+
+        let zero = Polynomial::new(&fp, vec![]);
+        let a = random_poly(&fp, 3);
+        assert_eq!(a.gcd(&zero).coefficients(), a.monic().coefficients());
+        assert!(zero.gcd(&zero).is_zero());
+        assert!(a.lcm(&zero).is_zero());
+        let (g, s, t) = zero.xgcd(&a);
+        assert_eq!(g.coefficients(), a.monic().coefficients());
+        assert!(s.is_zero());
+        assert_eq!((t * a).coefficients(), g.coefficients());
+    }
+
+    /// The polynomial of the given integer coefficients from the constant term
+    fn poly(fp : &FiniteField, coefficient : &[i64]) -> Polynomial<FiniteField> {
+        Polynomial::new(fp, coefficient.iter().map(|c| fp.gen(&BigInt::from(*c))).collect())
+    }
+
+    #[test]
+    fn roots_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        let expected : Vec<i64> = vec![0, 1, 5, 17, 42, 98];
+        let mut f = poly(&fp, &[-5, 0, 1]);
+        for r in expected.iter() {
+            f = f * poly(&fp, &[-r, 1]);
+        }
+        // a double root and an irreducible cubic don't add roots
+        f = f * poly(&fp, &[-5, 1]) * poly(&fp, &[-2, 0, 0, 1]);
+
+        let mut roots : Vec<BigInt> = f.roots().into_iter().map(|r| BigInt::from(r.to_bytes()[0])).collect();
+        roots.sort();
+        assert_eq!(roots, expected.into_iter().map(BigInt::from).collect::<Vec<_>>());
+        assert!(poly(&fp, &[-5, 0, 1]).roots().is_empty());
+
+        // the square roots in F_{p^2}
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(102_u32));
+        for _ in 0..10 {
+            let b = fp2.rand(None);
+            let a = b.clone() * b.clone();
+            let f = Polynomial::new(&fp2, vec![-a.clone(), fp2.zero(), fp2.one()]);
+            let roots = f.roots();
+            assert_eq!(roots.len(), if a.is_zero() { 1 } else { 2 });
+            assert!(roots.contains(&b) && roots.contains(&-b));
+
+            let c = a * fp2.gen((&BigInt::from(1), &BigInt::from(1)));
+            let f = Polynomial::new(&fp2, vec![-c.clone(), fp2.zero(), fp2.one()]);
+            assert_eq!(f.roots().len() == 2, !c.is_zero() && c.is_square());
+        }
+    }
+
+    #[test]
+    fn factor_test() {
+        // (x + 1)^5 * (x + 2)^2 * (x^2 + 2)^3 * x over F_5, where x^2 + 2 is irreducible
+        let fp = FiniteField::new(&BigUint::from(5_u32));
+        let mut f = poly(&fp, &[0, 3]);
+        for (factor, m) in [(poly(&fp, &[1, 1]), 5), (poly(&fp, &[2, 1]), 2), (poly(&fp, &[2, 0, 1]), 3)] {
+            for _ in 0..m {
+                f = f * factor.clone();
+            }
+        }
+
+        let factors : Vec<(Vec<_>, usize)> = f.factor().into_iter()
+            .map(|(g, m)| (g.coefficients().iter().map(|c| c.to_bytes()[0]).collect(), m))
+            .collect();
+        assert_eq!(factors.len(), 4);
+        for expected in [(vec![0, 1], 1), (vec![1, 1], 5), (vec![2, 1], 2), (vec![2, 0, 1], 3)] {
+            assert!(factors.contains(&expected), "{expected:?} is missing in {factors:?}");
+        }
+        assert_eq!(factors.last().unwrap(), &(vec![2, 0, 1], 3));
+
+        // x^8 - x over F_2 is the product of the irreducible polynomials of degree 1 and 3
+        let f2 = FiniteField::new(&BigUint::from(2_u32));
+        let f = poly(&f2, &[0, -1, 0, 0, 0, 0, 0, 0, 1]);
+        assert!(f.squarefree_factorization().len() == 1);
+        let ddf : Vec<usize> = f.distinct_degree_factorization().into_iter().map(|(g, d)| g.degree() / d).collect();
+        assert_eq!(ddf, vec![2, 2]);
+        let factors : Vec<Vec<u8>> = f.factor().into_iter()
+            .map(|(g, _)| g.coefficients().iter().map(|c| c.to_bytes()[0]).collect())
+            .collect();
+        assert_eq!(factors.len(), 4);
+        for expected in [vec![0, 1], vec![1, 1], vec![1, 1, 0, 1], vec![1, 0, 1, 1]] {
+            assert!(factors.contains(&expected));
+        }
+    }
+
+    #[test]
+    fn is_irreducible_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        assert!(poly(&fp, &[-5, 1]).is_irreducible());
+        assert!(poly(&fp, &[-5, 0, 1]).is_irreducible());
+        assert!(poly(&fp, &[-2, 0, 0, 1]).is_irreducible());
+        assert!(!poly(&fp, &[-4, 0, 1]).is_irreducible());
+        assert!(!(poly(&fp, &[-5, 0, 1]) * poly(&fp, &[-2, 0, 0, 1])).is_irreducible());
+        assert!(!(poly(&fp, &[-5, 0, 1]) * poly(&fp, &[-5, 0, 1])).is_irreducible());
+        assert!(!poly(&fp, &[7]).is_irreducible());
+
+        // the number of monic irreducible quartics over F_5 is (5^4 - 5^2) / 4 = 150
+        let f5 = FiniteField::new(&BigUint::from(5_u32));
+        let mut count = 0;
+        for n in 0..625 {
+            let f = poly(&f5, &[n % 5, n / 5 % 5, n / 25 % 5, n / 125, 1]);
+            if f.is_irreducible() {
+                count += 1;
+            }
+        }
+        assert_eq!(count, 150);
+    }
+
+    #[test]
+    fn karatsuba_test() {
+        let fp = FiniteField::new(&BigUint::from(1000003_u32));
+        let zero = fp.zero();
+        for (len_a, len_b) in [(1, 1), (11, 13), (12, 12), (17, 16), (40, 3), (9, 100), (64, 65), (100, 37)] {
+            let a : Vec<_> = (0..len_a).map(|_| fp.rand(None)).collect();
+            let b : Vec<_> = (0..len_b).map(|_| fp.rand(None)).collect();
+            let product = (Polynomial::new(&fp, a.clone()) * Polynomial::new(&fp, b.clone())).coefficients().to_vec();
+            assert_eq!(product, super::schoolbook(&a, &b, &zero));
+        }
+    }
+
+    #[test]
+    fn subproduct_tree_test() {
+        let fp = FiniteField::new(&BigUint::from(1000003_u32));
+        let horner = |f : &Polynomial<FiniteField>, a : &FiniteFieldElement| {
+            f.coefficients().iter().rev().fold(fp.zero(), |acc, c| acc * a.clone() + c.clone())
+        };
+
+        for n in [1, 2, 5, 16, 33, 100] {
+            let points : Vec<_> = (0..n).map(|i| fp.gen(&BigInt::from(3 * i + 1))).collect();
+            let f = random_poly(&fp, 2 * n);
+            let values = f.multipoint_evaluate(&points);
+            let expected : Vec<_> = points.iter().map(|a| horner(&f, a)).collect();
+            assert_eq!(values, expected);
+
+            let tree = SubproductTree::new(&fp, &points);
+            assert_eq!(tree.root().degree(), n);
+            assert!(points.iter().all(|a| horner(&tree.root(), a).is_zero()));
+
+            // the interpolation of the values of a polynomial of degree < n is itself
+            let g = random_poly(&fp, n - 1);
+            let interpolated = Polynomial::interpolate(&fp, &points, &g.multipoint_evaluate(&points));
+            assert_eq!(interpolated.coefficients(), g.coefficients());
+            assert_eq!(tree.interpolate(&values).coefficients(), (f.clone() % tree.root()).coefficients());
+        }
+        assert!(Polynomial::interpolate(&fp, &[], &[]).is_zero());
+    }
+
+    #[test]
+    fn eval_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        let c = |n : i64| fp.gen(&BigInt::from(n));
+
+        // normalization
+        assert!(Polynomial::new(&fp, vec![c(0), c(0)]).is_zero());
+        assert_eq!(Polynomial::new(&fp, vec![c(3), c(1), c(0)]), poly(&fp, &[3, 1]));
+        assert_ne!(poly(&fp, &[3, 1]), poly(&fp, &[3, 2]));
+        assert_eq!(poly(&fp, &[1, 2]) - poly(&fp, &[1, 2]), Polynomial::new(&fp, vec![]));
+        assert_eq!(Polynomial::monomial(&fp, c(5), 3), poly(&fp, &[0, 0, 0, 5]));
+        assert!(Polynomial::monomial(&fp, c(0), 3).is_zero());
+
+        // the top coefficients are added too, and a cancelling top term lowers the degree
+        assert_eq!(poly(&fp, &[1, 2]) + poly(&fp, &[3, 4, 5]), poly(&fp, &[4, 6, 5]));
+        assert_eq!(poly(&fp, &[1, 2, 5]) + poly(&fp, &[3, 4, -5]), poly(&fp, &[4, 6]));
+        assert_eq!((poly(&fp, &[1, 2, 5]) + poly(&fp, &[3, 4, -5])).degree(), 1);
+
+        let roots : Vec<_> = [2, 3, 5, 7].map(c).to_vec();
+        let f = Polynomial::from_roots(&fp, &roots);
+        assert_eq!(f, poly(&fp, &[-2, 1]) * poly(&fp, &[-3, 1]) * poly(&fp, &[-5, 1]) * poly(&fp, &[-7, 1]));
+        assert!(roots.iter().all(|r| f.eval(r).is_zero()));
+        assert_eq!(f.eval(&c(0)), c(210));
+        assert_eq!(Polynomial::from_roots(&fp, &[]), poly(&fp, &[1]));
+
+        // f' = sum of prod_{j != i} (x - r_j)
+        let derivative = (0..4).fold(Polynomial::new(&fp, vec![]), |acc, i| {
+            let others : Vec<_> = roots.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, r)| r.clone()).collect();
+            acc + Polynomial::from_roots(&fp, &others)
+        });
+        assert_eq!(f.derivative(), derivative);
+        assert!(poly(&fp, &[4]).derivative().is_zero());
+
+        let g = poly(&fp, &[1, 0, 2]);
+        let h = f.compose(&g);
+        assert_eq!(h.degree(), 8);
+        for a in (0..10).map(c) {
+            assert_eq!(h.eval(&a), f.eval(&g.eval(&a)));
+        }
+
+        assert_eq!(poly(&fp, &[1, 2, 3]).reverse(), poly(&fp, &[3, 2, 1]));
+        assert_eq!(poly(&fp, &[0, 2, 3]).reverse(), poly(&fp, &[3, 2]));
+        assert_eq!(f.scale(&c(2)), f.clone() * BigInt::from(2));
+        assert_eq!(f.clone() + f.clone(), f.scale(&c(2)));
+
+        // x^2 - 5 has the roots ±z in F_{103^2} = F_103[z] / (z^2 - 5)
+        let modulus = poly(&fp, &[-5, 0, 1]);
+        let fq = ExtensionField::new(&fp, &modulus);
+        assert!(modulus.eval_extension(&fq, &fq.generator()).is_zero());
+        assert!(modulus.eval_extension(&fq, &-fq.generator()).is_zero());
+        assert_eq!(f.eval_extension(&fq, &fq.embed(&c(11))), fq.embed(&f.eval(&c(11))));
+    }
+
+    #[test]
+    fn polynomial_display_test() {
+        let fp = FiniteField::new(&BigUint::from(431_u32));
+        let fp2 = FiniteField2::new(&fp, &BigUint::from(430_u32));
+        let c = |a : i32, b : i32| fp2.gen((&BigInt::from(a), &BigInt::from(b)));
+
+        let poly = Polynomial::new(&fp2, vec![c(1, 0), c(1, 0), c(3, 6), c(0, 0), c(0, 2)]);
+        assert_eq!(poly.to_string(), "2*i*x^4 + (6*i + 3)*x^2 + x + 1");
+        let parsed = Polynomial::from_str(&fp2, &poly.to_string()).unwrap();
+        assert_eq!(parsed.coefficients(), poly.coefficients());
+
+        assert_eq!(Polynomial::new(&fp2, vec![]).to_string(), "0");
+        assert_eq!(Polynomial::new(&fp2, vec![c(0, 1)]).to_string(), "i");
+        let parsed = Polynomial::from_str(&fp2, "x^3 - (i + 1)*x + 2*x").unwrap();
+        assert_eq!(parsed.coefficients(), &[c(0, 0), c(1, -1), c(0, 0), c(1, 0)]);
+        assert!(Polynomial::from_str(&fp2, "x^3 + y").is_err());
+    }
+
+    #[test]
+    fn resultant_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        let c = |n : i64| fp.gen(&BigInt::from(n));
+
+        // Res(a, b) = prod (r_i - s_j) for monic a and b
+        let (r, s) = ([2, 3, 5].map(c), [7, 11].map(c));
+        let (a, b) = (Polynomial::from_roots(&fp, &r), Polynomial::from_roots(&fp, &s));
+        let expected = r.iter().flat_map(|x| s.iter().map(move |y| x.clone() - y.clone())).fold(c(1), |acc, d| acc * d);
+        assert_eq!(a.resultant(&b), expected);
+        assert_eq!(b.resultant(&a), expected);
+        assert_eq!(a.scale(&c(2)).resultant(&b), expected.clone() * c(4));
+        assert_eq!(a.resultant(&poly(&fp, &[5])), c(125));
+        assert!(a.resultant(&(a.clone() * b.clone())).is_zero());
+        assert!(a.resultant(&Polynomial::new(&fp, vec![])).is_zero());
+
+        // the formal degrees of the Sylvester matrix
+        assert_eq!(a.resultant_with_degrees(&b, 3, 2), expected);
+        assert_eq!(a.resultant_with_degrees(&b, 3, 4), expected);
+        assert_eq!(b.resultant_with_degrees(&a, 4, 3), expected);
+        assert_eq!(b.resultant_with_degrees(&a, 3, 3), -expected.clone());
+        assert_eq!(a.scale(&c(2)).resultant_with_degrees(&b, 3, 4), expected * c(16));
+        assert!(a.resultant_with_degrees(&b, 4, 3).is_zero());
+        assert_eq!(poly(&fp, &[3]).resultant_with_degrees(&b, 0, 2), c(9));
+    }
+
+    #[test]
+    fn discriminant_test() {
+        let fp = FiniteField::new(&BigUint::from(103_u32));
+        let c = |n : i64| fp.gen(&BigInt::from(n));
+        assert_eq!(poly(&fp, &[5, 3, 1]).discriminant(), c(9 - 20));
+        assert_eq!(poly(&fp, &[5, 3, 2]).discriminant(), c(9 - 40));
+        assert_eq!(poly(&fp, &[7, 4, 0, 1]).discriminant(), c(-4 * 64 - 27 * 49));
+        assert_eq!(poly(&fp, &[7, 4]).discriminant(), c(1));
+
+        // prod_{i < j} (r_i - r_j)^2 for a monic polynomial
+        let roots = [2, 3, 5, 7].map(c);
+        let mut expected = c(1);
+        for i in 0..4 {
+            for j in i + 1..4 {
+                expected = expected * (roots[i].clone() - roots[j].clone()).pow(&BigInt::from(2));
+            }
+        }
+        assert_eq!(Polynomial::from_roots(&fp, &roots).discriminant(), expected);
+        assert!(Polynomial::from_roots(&fp, &[c(2), c(2), c(5)]).discriminant().is_zero());
+
+        // the derivative drops degree in small characteristic
+        let f3 = FiniteField::new(&BigUint::from(3_u32));
+        assert_eq!(poly(&f3, &[1, 2, 0, 1]).discriminant(), f3.gen(&BigInt::from(-4 * 8)));
+        assert_eq!(poly(&f3, &[0, -1, 0, 1]).discriminant(), f3.gen(&BigInt::from(4)));
+        let f2 = FiniteField::new(&BigUint::from(2_u32));
+        assert_eq!(poly(&f2, &[1, 1, 1]).discriminant(), f2.one());
+        assert!(poly(&f2, &[1, 0, 1]).discriminant().is_zero());
+    }
+}
@@ -0,0 +1,249 @@
+//! Human-readable formatting in the syntax of SageMath
+//!
+//! The `Display` implementations print what Sage prints for the same objects,
+//! e.g. `6*i + 3` in F_{p^2} = F_p[i], `(6*i + 3)*x^2 + x + 1` for polynomials and `(x : y : 1)` for points,
+//! so the outputs can be pasted into Sage once the generators are defined.
+//! The parsers read the same format back : sums of terms `c*v^e` where c may be a parenthesized sum.
+use std::fmt;
+use std::str::FromStr;
+use num::{BigInt, Zero};
+
+/// Error of the parsers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input isn't a sum of terms of the expected form
+    InvalidSyntax,
+    /// A coefficient isn't a number
+    InvalidNumber,
+    /// The coordinates don't satisfy the curve equation
+    NotOnCurve,
+    /// The coefficient defines a singular curve
+    SingularCurve,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidSyntax => write!(f, "invalid syntax"),
+            ParseError::InvalidNumber => write!(f, "invalid number"),
+            ParseError::NotOnCurve => write!(f, "the point is not on the curve"),
+            ParseError::SingularCurve => write!(f, "the curve is singular"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Whether a formatted value is a sum of several terms, which needs parentheses as a coefficient
+fn is_sum(s : &str) -> bool {
+    split_terms(s).is_ok_and(|terms| terms.len() > 1)
+}
+
+/// Write the sum of the terms (coefficient, monomial) in the given order as Sage does
+/// Zero terms are omitted, unit coefficients are dropped and the sums are parenthesized
+/// The empty monomial is the constant term
+pub(crate) fn write_sum(f : &mut fmt::Formatter, terms : &[(String, String)]) -> fmt::Result {
+    let mut first = true;
+    for (coefficient, monomial) in terms {
+        if coefficient == "0" {
+            continue;
+        }
+
+        let (negative, coefficient) = match coefficient.strip_prefix('-') {
+            Some(abs) if !is_sum(coefficient) => (true, abs),
+            _ => (false, coefficient.as_str()),
+        };
+        match (first, negative) {
+            (true, true) => write!(f, "-")?,
+            (true, false) => (),
+            (false, true) => write!(f, " - ")?,
+            (false, false) => write!(f, " + ")?,
+        }
+        first = false;
+
+        match (monomial.is_empty(), coefficient) {
+            (true, _) => write!(f, "{coefficient}")?,
+            (false, "1") => write!(f, "{monomial}")?,
+            (false, _) if is_sum(coefficient) => write!(f, "({coefficient})*{monomial}")?,
+            (false, _) => write!(f, "{coefficient}*{monomial}")?,
+        }
+    }
+
+    if first {
+        write!(f, "0")?;
+    }
+    Ok(())
+}
+
+/// A term of a parsed sum
+/// variable is the index in the given variables, or None for the constant term
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Term<'a> {
+    pub negative : bool,
+    pub coefficient : &'a str,
+    pub variable : Option<usize>,
+    pub exponent : usize,
+}
+
+/// Split a sum at the top-level binary + and -, keeping the signs with the terms
+fn split_terms(s : &str) -> Result<Vec<&str>, ParseError> {
+    let mut terms = vec![];
+    let mut depth = 0_usize;
+    let mut start = 0;
+    let mut previous = None;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1).ok_or(ParseError::InvalidSyntax)?,
+            '+' | '-' if depth == 0 && previous.is_some_and(|p| !"*^/(+-".contains(p)) => {
+                terms.push(s[start..i].trim());
+                start = i;
+            },
+            _ => (),
+        }
+        if !c.is_whitespace() {
+            previous = Some(c);
+        }
+    }
+    if depth != 0 {
+        return Err(ParseError::InvalidSyntax);
+    }
+    terms.push(s[start..].trim());
+
+    match terms.iter().any(|term| term.is_empty()) {
+        true => Err(ParseError::InvalidSyntax),
+        false => Ok(terms),
+    }
+}
+
+/// Remove the parentheses around the whole string
+fn strip_parens(s : &str) -> &str {
+    let s = s.trim();
+    if !(s.starts_with('(') && s.ends_with(')')) {
+        return s;
+    }
+
+    // the opening parenthesis must be closed at the end
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => (),
+        }
+        if depth == 0 {
+            return match i == s.len() - 1 {
+                true => strip_parens(&s[1..i]),
+                false => s,
+            };
+        }
+    }
+    s
+}
+
+/// Parse a monomial v or v^e of one of the variables
+fn parse_monomial(s : &str, variables : &[&str]) -> Option<(usize, usize)> {
+    let (name, exponent) = match s.trim().split_once('^') {
+        Some((name, exponent)) => (name.trim(), exponent.trim().parse().ok()?),
+        None => (s.trim(), 1),
+    };
+    variables.iter().position(|variable| *variable == name).map(|index| (index, exponent))
+}
+
+/// Parse a sum of terms c*v^e, c*v, v^e, v or c for the given variables
+/// The coefficients are returned unparsed without their parentheses and signs
+pub(crate) fn parse_sum<'a>(s : &'a str, variables : &[&str]) -> Result<Vec<Term<'a>>, ParseError> {
+    let s = strip_parens(s);
+    if s.is_empty() {
+        return Err(ParseError::InvalidSyntax);
+    }
+
+    split_terms(s)?.into_iter().map(|term| {
+        let (negative, term) = match term.strip_prefix('-') {
+            Some(term) => (true, term.trim()),
+            None => (false, term.strip_prefix('+').unwrap_or(term).trim()),
+        };
+
+        // the monomial follows the last top-level *
+        let mut depth = 0;
+        let mut split = None;
+        for (i, c) in term.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                '*' if depth == 0 => split = Some(i),
+                _ => (),
+            }
+        }
+
+        let (coefficient, monomial) = match split {
+            Some(i) => match parse_monomial(&term[i + 1..], variables) {
+                Some(monomial) => (strip_parens(&term[..i]), Some(monomial)),
+                None => (strip_parens(term), None),
+            },
+            None => match parse_monomial(term, variables) {
+                Some(monomial) => ("1", Some(monomial)),
+                None => (strip_parens(term), None),
+            },
+        };
+        if coefficient.is_empty() {
+            return Err(ParseError::InvalidSyntax);
+        }
+
+        Ok(Term {
+            negative,
+            coefficient,
+            variable : monomial.map(|(variable, _)| variable),
+            exponent : monomial.map_or(0, |(_, exponent)| exponent),
+        })
+    }).collect()
+}
+
+/// Parse an integer, which may be negative or parenthesized
+pub(crate) fn parse_integer(s : &str) -> Result<BigInt, ParseError> {
+    BigInt::from_str(strip_parens(s)).map_err(|_| ParseError::InvalidNumber)
+}
+
+/// Parse a + b*i with integer coefficients as (a, b)
+pub(crate) fn parse_quadratic(s : &str) -> Result<(BigInt, BigInt), ParseError> {
+    let mut coordinates = [BigInt::zero(), BigInt::zero()];
+    for term in parse_sum(s, &["i"])? {
+        let index = match (term.variable, term.exponent) {
+            (None, _) => 0,
+            (Some(_), 1) => 1,
+            _ => return Err(ParseError::InvalidSyntax),
+        };
+        let coefficient = parse_integer(term.coefficient)?;
+        match term.negative {
+            true => coordinates[index] -= coefficient,
+            false => coordinates[index] += coefficient,
+        }
+    }
+    let [a, b] = coordinates;
+    Ok((a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_sum, split_terms, Term, ParseError};
+
+    #[test]
+    fn parse_sum_test() {
+        let term = |negative, coefficient, variable, exponent| Term { negative, coefficient, variable, exponent };
+        assert_eq!(parse_sum("(3*i + 1)*x^2 - x + 5*i", &["x"]), Ok(vec![
+            term(false, "3*i + 1", Some(0), 2),
+            term(true, "1", Some(0), 1),
+            term(false, "5*i", None, 0),
+        ]));
+        assert_eq!(parse_sum("-3/4 + i - 2*j + 1/2*k", &["i", "j", "k"]), Ok(vec![
+            term(true, "3/4", None, 0),
+            term(false, "1", Some(0), 1),
+            term(true, "2", Some(1), 1),
+            term(false, "1/2", Some(2), 1),
+        ]));
+        assert_eq!(parse_sum("(x^2 + 1)", &["x"]).map(|terms| terms.len()), Ok(2));
+        assert_eq!(split_terms("(1 + 2"), Err(ParseError::InvalidSyntax));
+        assert_eq!(parse_sum("1 + ", &["x"]), Err(ParseError::InvalidSyntax));
+        assert_eq!(parse_sum("", &["x"]), Err(ParseError::InvalidSyntax));
+    }
+}